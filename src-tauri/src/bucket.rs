@@ -0,0 +1,1108 @@
+use crate::logging::redact_error;
+use crate::object::{delete_keys, OssDeleteFailure};
+use crate::oss::{
+    execute_signed, list_all_objects, list_objects_page, load_oss_config, remember_correction, request_url,
+    resolve_region_and_endpoint, signed_client, EndpointCorrection, OssConfig, OssResult, RetryPolicy, SignedRequest,
+};
+use crate::vault::VaultState;
+use crate::xml;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, RANGE};
+use futures_util::stream::{self, StreamExt};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::{command, State, Window};
+
+const BUCKET_POLICY: RetryPolicy = RetryPolicy::new(30, "请求超时，请检查网络或 Endpoint 配置");
+const SECURITY_POLICY: RetryPolicy = RetryPolicy::new(12, "请求超时，请检查网络或 Endpoint 配置");
+
+const CANNED_ACLS: [&str; 4] = ["private", "public-read", "public-read-write", "authenticated-read"];
+
+// 常见错误码对应的提示，其余错误原样返回
+const BUCKET_ERRORS: [(&str, &str); 6] = [
+    ("BucketAlreadyExists", "存储桶名称已被占用，请换一个名称"),
+    ("BucketAlreadyOwnedByYou", "存储桶已存在"),
+    ("TooManyBuckets", "存储桶数量已达上限"),
+    ("InvalidLocationConstraint", "区域不正确或与 Endpoint 不匹配"),
+    ("BucketNotEmpty", "存储桶不为空（可能仍有历史版本或未完成的分片上传）"),
+    ("NoSuchBucket", "存储桶不存在"),
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OssCreateBucketParams {
+    pub bucket: String,
+    // 为空时使用配置中的区域
+    pub region: Option<String>,
+    // private / public-read / public-read-write / authenticated-read
+    pub acl: Option<String>,
+    // 默认存储类型，仅阿里云（Standard/IA/Archive/ColdArchive）与华为云（STANDARD/WARM/COLD）支持
+    pub storage_class: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OssDeleteBucketParams {
+    pub bucket: String,
+    // 需再次输入存储桶名称确认
+    pub confirm_name: String,
+    // 先删除桶内全部对象
+    pub empty_first: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssDeleteBucketSummary {
+    pub bucket: String,
+    pub deleted_objects: u32,
+    pub failed: Vec<OssDeleteFailure>,
+}
+
+// 存储桶命名规则取各厂商的交集：3-63 位小写字母、数字和连字符，首尾为字母或数字
+fn validate_bucket_name(name: &str) -> Result<(), String> {
+    let valid_chars = name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    let valid_ends = !name.starts_with('-') && !name.ends_with('-');
+    if !(3..=63).contains(&name.len()) || !valid_chars || !valid_ends {
+        return Err("存储桶名称需为 3-63 位小写字母、数字或连字符，且不能以连字符开头或结尾".to_string());
+    }
+    Ok(())
+}
+
+fn bucket_error(error: String) -> String {
+    BUCKET_ERRORS
+        .iter()
+        .find(|(code, _)| error.contains(&format!("<Code>{}</Code>", code)))
+        .map(|(_, hint)| hint.to_string())
+        .unwrap_or(error)
+}
+
+// 请求发往目标存储桶；区域覆盖配置时，由厂商规则重新生成 endpoint
+fn bucket_config(cfg: &OssConfig, bucket: &str, region: Option<&str>) -> OssConfig {
+    let mut target = cfg.clone();
+    target.bucket = Some(bucket.to_string());
+    if let Some(region) = region.filter(|r| !r.is_empty() && Some(*r) != cfg.region.as_deref()) {
+        target.region = Some(region.to_string());
+        if target.provider != "minio" {
+            target.endpoint = None;
+        }
+    }
+    target
+}
+
+fn create_bucket_request(cfg: &OssConfig, params: &OssCreateBucketParams) -> Result<(HeaderMap, Vec<u8>), String> {
+    let mut headers = HeaderMap::new();
+    if let Some(acl) = params.acl.as_deref() {
+        let acl = CANNED_ACLS.into_iter().find(|a| *a == acl).ok_or_else(|| format!("不支持的访问权限: {}", acl))?;
+        headers.insert(HeaderName::from_static("x-amz-acl"), HeaderValue::from_static(acl));
+    }
+
+    let (_, _, location) = resolve_region_and_endpoint(cfg)?;
+    let mut config = String::new();
+    if let Some(location) = location {
+        config.push_str(&format!("<LocationConstraint>{}</LocationConstraint>", xml::escape(&location)));
+    }
+    if let Some(class) = params.storage_class.as_deref().filter(|c| !c.is_empty()) {
+        match cfg.provider.as_str() {
+            "aliyun" => config.push_str(&format!("<StorageClass>{}</StorageClass>", xml::escape(class))),
+            "huawei" => {
+                let value = HeaderValue::from_str(class).map_err(|_| format!("不支持的存储类型: {}", class))?;
+                headers.insert(HeaderName::from_static("x-default-storage-class"), value);
+            }
+            _ => return Err("当前云厂商不支持设置存储桶默认存储类型".to_string()),
+        }
+    }
+    if config.is_empty() {
+        return Ok((headers, Vec::new()));
+    }
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/xml"));
+    let body = format!("<CreateBucketConfiguration>{}</CreateBucketConfiguration>", config);
+    Ok((headers, body.into_bytes()))
+}
+
+// 新建存储桶与当前配置的存储桶无关，不返回也不回写 endpoint 修正
+#[command]
+pub async fn oss_create_bucket(cfg: OssConfig, params: OssCreateBucketParams) -> Result<OssResult<String>, String> {
+    validate_bucket_name(&params.bucket)?;
+    let target = bucket_config(&cfg, &params.bucket, params.region.as_deref());
+    let (headers, body) = create_bucket_request(&target, &params)?;
+    execute_signed(&target, BUCKET_POLICY, &SignedRequest { method: Method::PUT, key: None, query: &[], headers, body })
+        .await
+        .map_err(bucket_error)?;
+
+    Ok(OssResult {
+        success: true,
+        message: Some(format!("存储桶 {} 创建成功", params.bucket)),
+        data: Some(params.bucket),
+        error: None,
+        correction: None,
+    })
+}
+
+// 删除存储桶；empty_first 时先删除桶内全部对象，部分对象删除失败则保留存储桶
+#[command]
+pub async fn oss_delete_bucket(
+    window: Window,
+    cfg: OssConfig,
+    params: OssDeleteBucketParams,
+) -> Result<OssResult<OssDeleteBucketSummary>, String> {
+    if params.confirm_name != params.bucket {
+        return Err("确认名称与存储桶名称不一致".to_string());
+    }
+    let target = bucket_config(&cfg, &params.bucket, None);
+    let mut summary = OssDeleteBucketSummary { bucket: params.bucket.clone(), deleted_objects: 0, failed: Vec::new() };
+
+    if params.empty_first.unwrap_or(false) {
+        let objects = list_all_objects(&target, "").await.map_err(bucket_error)?;
+        let keys: Vec<String> = objects.value.into_iter().map(|o| o.key).collect();
+        (summary.deleted_objects, summary.failed) = delete_keys(&window, &target, "", &keys).await.value;
+        if !summary.failed.is_empty() {
+            return Ok(OssResult {
+                success: false,
+                message: None,
+                error: Some(format!("{} 个对象删除失败，存储桶未删除", summary.failed.len())),
+                data: Some(summary),
+                correction: None,
+            });
+        }
+    }
+
+    execute_signed(
+        &target,
+        BUCKET_POLICY,
+        &SignedRequest { method: Method::DELETE, key: None, query: &[], headers: HeaderMap::new(), body: Vec::new() },
+    )
+    .await
+    .map_err(bucket_error)?;
+
+    Ok(OssResult {
+        success: true,
+        message: Some(format!("存储桶 {} 已删除", params.bucket)),
+        data: Some(summary),
+        error: None,
+        correction: None,
+    })
+}
+
+#[command]
+pub async fn oss_create_bucket_by_id(
+    vault: State<'_, VaultState>,
+    config_id: String,
+    params: OssCreateBucketParams,
+) -> Result<OssResult<String>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    oss_create_bucket(cfg, params).await
+}
+
+#[command]
+pub async fn oss_delete_bucket_by_id(
+    window: Window,
+    vault: State<'_, VaultState>,
+    config_id: String,
+    params: OssDeleteBucketParams,
+) -> Result<OssResult<OssDeleteBucketSummary>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    oss_delete_bucket(window, cfg, params).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OssBucketSecurityParams {
+    // 需要检查 ACL 的对象；为空时取桶内前 sample_size 个对象
+    pub sample_keys: Option<Vec<String>>,
+    pub sample_size: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssAclGrant {
+    // Group / CanonicalUser / AmazonCustomerByEmail
+    pub grantee_type: String,
+    // 组为 URI，用户为 ID 或邮箱
+    pub grantee: String,
+    pub display_name: Option<String>,
+    pub permission: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssAcl {
+    pub owner_id: Option<String>,
+    pub owner_display_name: Option<String>,
+    pub grants: Vec<OssAclGrant>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OssPublicAccessBlock {
+    pub block_public_acls: bool,
+    pub ignore_public_acls: bool,
+    pub block_public_policy: bool,
+    pub restrict_public_buckets: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssObjectAclSample {
+    pub key: String,
+    pub acl: Option<OssAcl>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssBucketSecurity {
+    pub bucket: String,
+    pub acl: Option<OssAcl>,
+    // 未设置策略时为空
+    pub policy: Option<Value>,
+    pub policy_error: Option<String>,
+    // 厂商不支持或未配置时为空
+    pub public_access_block: Option<OssPublicAccessBlock>,
+    pub public_access_block_error: Option<String>,
+    pub object_acls: Vec<OssObjectAclSample>,
+    // 凭证列举失败、无法抽样对象时的原因，此时不检查对象 ACL
+    pub sample_error: Option<String>,
+    // 命中的风险：public-write / public-read / authenticated-users
+    pub risks: Vec<String>,
+    // 最严重的风险；无风险时为 private，有配置读取失败时为 unknown
+    pub verdict: String,
+    pub findings: Vec<String>,
+}
+
+const RISK_PUBLIC_WRITE: &str = "public-write";
+const RISK_PUBLIC_READ: &str = "public-read";
+const RISK_AUTHENTICATED: &str = "authenticated-users";
+const VERDICT_PRIVATE: &str = "private";
+const VERDICT_UNKNOWN: &str = "unknown";
+// 按严重程度排序
+const RISK_LEVELS: [&str; 3] = [RISK_PUBLIC_WRITE, RISK_PUBLIC_READ, RISK_AUTHENTICATED];
+const DEFAULT_ACL_SAMPLES: u32 = 5;
+const MAX_ACL_SAMPLES: u32 = 20;
+const ACL_SAMPLE_CONCURRENCY: usize = 4;
+
+fn parse_acl(body: &str) -> OssAcl {
+    let owner = xml::blocks(body, "Owner").first().copied().unwrap_or_default();
+    let grants = xml::blocks(body, "Grant")
+        .into_iter()
+        .filter_map(|grant| {
+            let grantee = xml::blocks(grant, "Grantee").first().copied()?;
+            let (grantee_type, id) = [("Group", "URI"), ("CanonicalUser", "ID"), ("AmazonCustomerByEmail", "EmailAddress")]
+                .into_iter()
+                .find_map(|(kind, tag)| Some((kind, xml::text(grantee, tag)?)))?;
+            Some(OssAclGrant {
+                grantee_type: grantee_type.to_string(),
+                grantee: id,
+                display_name: xml::text(grantee, "DisplayName"),
+                permission: xml::text(grant, "Permission")?,
+            })
+        })
+        .collect();
+    OssAcl { owner_id: xml::text(owner, "ID"), owner_display_name: xml::text(owner, "DisplayName"), grants }
+}
+
+fn parse_public_access_block(body: &str) -> OssPublicAccessBlock {
+    let flag = |tag| xml::text(body, tag).is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    OssPublicAccessBlock {
+        block_public_acls: flag("BlockPublicAcls"),
+        ignore_public_acls: flag("IgnorePublicAcls"),
+        block_public_policy: flag("BlockPublicPolicy"),
+        restrict_public_buckets: flag("RestrictPublicBuckets"),
+    }
+}
+
+// 组 URI 各厂商前缀不同（acs.amazonaws.com、cam.qcloud.com 等），按结尾判断
+fn acl_risks(acl: &OssAcl) -> Vec<(&'static str, String)> {
+    let mut risks = Vec::new();
+    for grant in acl.grants.iter().filter(|g| g.grantee_type == "Group") {
+        let permission = grant.permission.as_str();
+        if grant.grantee.ends_with("/AllUsers") {
+            if matches!(permission, "WRITE" | "WRITE_ACP" | "FULL_CONTROL") {
+                risks.push((RISK_PUBLIC_WRITE, permission.to_string()));
+            }
+            if matches!(permission, "READ" | "READ_ACP" | "FULL_CONTROL") {
+                risks.push((RISK_PUBLIC_READ, permission.to_string()));
+            }
+        } else if grant.grantee.ends_with("/AuthenticatedUsers") {
+            risks.push((RISK_AUTHENTICATED, permission.to_string()));
+        }
+    }
+    risks
+}
+
+fn is_anonymous_principal(principal: &Value) -> bool {
+    match principal {
+        Value::String(s) => s == "*",
+        Value::Array(items) => items.iter().any(is_anonymous_principal),
+        Value::Object(map) => map.values().any(is_anonymous_principal),
+        _ => false,
+    }
+}
+
+fn as_list(value: Option<&Value>) -> Vec<&Value> {
+    match value {
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(value) => vec![value],
+        None => Vec::new(),
+    }
+}
+
+// 对任意主体（*）放行的 Allow 语句；写、删或通配动作视为公共写
+fn policy_risks(policy: &Value) -> Vec<(&'static str, String)> {
+    let mut risks = Vec::new();
+    for statement in as_list(policy.get("Statement")) {
+        let allow = statement.get("Effect").and_then(Value::as_str) == Some("Allow");
+        if !allow || !statement.get("Principal").is_some_and(is_anonymous_principal) {
+            continue;
+        }
+        let actions: Vec<&str> = as_list(statement.get("Action")).into_iter().filter_map(Value::as_str).collect();
+        let writes = actions.iter().any(|a| {
+            let verb = a.rsplit(':').next().unwrap_or(a);
+            verb == "*" || verb.starts_with("Put") || verb.starts_with("Delete")
+        });
+        let conditional = if statement.get("Condition").is_some() { "（带条件）" } else { "" };
+        let detail = format!("{}{}", actions.join(","), conditional);
+        risks.push((if writes { RISK_PUBLIC_WRITE } else { RISK_PUBLIC_READ }, detail));
+    }
+    risks
+}
+
+fn summarize_security(report: &mut OssBucketSecurity) {
+    let block = report.public_access_block.clone().unwrap_or_default();
+    let mut risks: Vec<&str> = Vec::new();
+    let mut findings = Vec::new();
+    let mut record = |source: String, found: Vec<(&'static str, String)>, blocked: bool| {
+        for (risk, detail) in found {
+            if blocked {
+                findings.push(format!("{}授予 {}（{}），已被公共访问阻止设置屏蔽", source, risk, detail));
+            } else {
+                findings.push(format!("{}授予 {}（{}）", source, risk, detail));
+                risks.push(risk);
+            }
+        }
+    };
+
+    if let Some(acl) = &report.acl {
+        record("存储桶 ACL ".to_string(), acl_risks(acl), block.ignore_public_acls);
+    }
+    if let Some(policy) = &report.policy {
+        record("存储桶策略".to_string(), policy_risks(policy), block.restrict_public_buckets);
+    }
+    for sample in &report.object_acls {
+        if let Some(acl) = &sample.acl {
+            record(format!("对象 {} 的 ACL ", sample.key), acl_risks(acl), block.ignore_public_acls);
+        }
+    }
+
+    // 任何一项读取失败都说明结论不完整：已发现的风险照常报告，否则不能判定为 private
+    let mut incomplete = Vec::new();
+    if report.policy_error.is_some() {
+        incomplete.push("存储桶策略".to_string());
+    }
+    if report.public_access_block_error.is_some() {
+        incomplete.push("公共访问阻止设置".to_string());
+    }
+    if report.sample_error.is_some() {
+        incomplete.push("对象抽样".to_string());
+    }
+    let failed_samples = report.object_acls.iter().filter(|s| s.error.is_some()).count();
+    if failed_samples > 0 {
+        incomplete.push(format!("{} 个对象的 ACL", failed_samples));
+    }
+    if !incomplete.is_empty() {
+        findings.push(format!("未能读取{}，检查结果不完整", incomplete.join("、")));
+    }
+
+    report.risks = RISK_LEVELS.iter().filter(|r| risks.contains(r)).map(|r| r.to_string()).collect();
+    let fallback = if incomplete.is_empty() { VERDICT_PRIVATE } else { VERDICT_UNKNOWN };
+    report.verdict = report.risks.first().cloned().unwrap_or_else(|| fallback.to_string());
+    report.findings = findings;
+}
+
+// 子资源不存在（未配置）时返回 None，其余错误原样返回；
+// 错误码按厂商列出，比较时忽略大小写（腾讯云的复制配置错误码为 ReplicationConfigurationnotFoundError）
+fn optional_config<T>(result: Result<T, String>, missing_codes: &[&str]) -> (Option<T>, Option<String>) {
+    match result {
+        Ok(value) => (Some(value), None),
+        Err(e) if has_error_code(&e, missing_codes) => (None, None),
+        Err(e) => (None, Some(e)),
+    }
+}
+
+fn has_error_code(error: &str, codes: &[&str]) -> bool {
+    let error = error.to_ascii_lowercase();
+    codes.iter().any(|code| error.contains(&format!("<code>{}</code>", code.to_ascii_lowercase())))
+}
+
+async fn fetch_subresource(
+    cfg: &mut OssConfig,
+    correction: &mut Option<EndpointCorrection>,
+    key: Option<&str>,
+    subresource: &str,
+) -> Result<String, String> {
+    let outcome = execute_signed(
+        cfg,
+        SECURITY_POLICY,
+        &SignedRequest { method: Method::GET, key, query: &[(subresource, "")], headers: HeaderMap::new(), body: Vec::new() },
+    )
+    .await?;
+    if let Some(c) = outcome.correction {
+        c.apply(cfg);
+        *correction = Some(c);
+    }
+    Ok(outcome.value.body)
+}
+
+// 汇总存储桶 ACL、策略、公共访问阻止设置与抽样对象 ACL，给出公开风险结论
+#[command]
+pub async fn oss_get_bucket_security(cfg: OssConfig, params: OssBucketSecurityParams) -> Result<OssResult<OssBucketSecurity>, String> {
+    let bucket = cfg.bucket.clone().ok_or_else(|| "Missing bucket name".to_string())?;
+    let mut cfg = cfg;
+    let mut correction = None;
+
+    // ACL 是所有厂商都支持的基础接口，失败时直接报错
+    let acl = parse_acl(&fetch_subresource(&mut cfg, &mut correction, None, "acl").await?);
+    let policy = fetch_subresource(&mut cfg, &mut correction, None, "policy")
+        .await
+        .and_then(|body| serde_json::from_str::<Value>(&body).map_err(|e| format!("存储桶策略格式错误: {}", e)));
+    let (policy, policy_error) = optional_config(policy, MISSING_POLICY);
+    let block = fetch_subresource(&mut cfg, &mut correction, None, "publicAccessBlock").await.map(|b| parse_public_access_block(&b));
+    let (public_access_block, public_access_block_error) = optional_config(block, MISSING_PUBLIC_ACCESS_BLOCK);
+
+    // 凭证没有列举权限时仍然返回已取得的 ACL、策略与公共访问阻止结果
+    let mut sample_error = None;
+    let keys = match params.sample_keys.filter(|k| !k.is_empty()) {
+        Some(keys) => keys,
+        None => {
+            let size = params.sample_size.unwrap_or(DEFAULT_ACL_SAMPLES).min(MAX_ACL_SAMPLES);
+            match list_objects_page(&cfg, "", "", None, None, size).await {
+                Ok(page) => {
+                    if let Some(c) = page.correction {
+                        c.apply(&mut cfg);
+                        correction = Some(c);
+                    }
+                    page.value.items.into_iter().map(|o| o.key).filter(|k| !k.ends_with('/')).collect()
+                }
+                Err(e) => {
+                    sample_error = Some(format!("列举样本对象失败，已跳过对象 ACL 检查: {}", e));
+                    Vec::new()
+                }
+            }
+        }
+    };
+    let sample_cfg = &cfg;
+    let object_acls = stream::iter(keys)
+        .map(|key| async move {
+            let outcome = execute_signed(
+                sample_cfg,
+                SECURITY_POLICY,
+                &SignedRequest { method: Method::GET, key: Some(&key), query: &[("acl", "")], headers: HeaderMap::new(), body: Vec::new() },
+            )
+            .await;
+            match outcome {
+                Ok(outcome) => OssObjectAclSample { key, acl: Some(parse_acl(&outcome.value.body)), error: None },
+                Err(error) => OssObjectAclSample { key, acl: None, error: Some(error) },
+            }
+        })
+        .buffered(ACL_SAMPLE_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut report = OssBucketSecurity {
+        bucket,
+        acl: Some(acl),
+        policy,
+        policy_error,
+        public_access_block,
+        public_access_block_error,
+        object_acls,
+        sample_error,
+        risks: Vec::new(),
+        verdict: String::new(),
+        findings: Vec::new(),
+    };
+    summarize_security(&mut report);
+
+    Ok(OssResult {
+        success: true,
+        message: Some(format!("安全检查完成：{}", report.verdict)),
+        data: Some(report),
+        error: None,
+        correction,
+    })
+}
+
+#[command]
+pub async fn oss_get_bucket_security_by_id(
+    vault: State<'_, VaultState>,
+    config_id: String,
+    params: OssBucketSecurityParams,
+) -> Result<OssResult<OssBucketSecurity>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_get_bucket_security(cfg, params).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OssAnonymousAccessParams {
+    // 为空时检查配置中的存储桶
+    pub bucket: Option<String>,
+    // 用于 get/head 的对象；为空时用凭证列举取桶内第一个对象
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssAnonymousCheck {
+    // list / get / head
+    pub operation: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub allowed: bool,
+    // 拒绝时响应中的错误码，如 AccessDenied
+    pub code: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssAnonymousAccess {
+    pub bucket: String,
+    pub key: Option<String>,
+    pub checks: Vec<OssAnonymousCheck>,
+    pub allowed_operations: Vec<String>,
+    // 凭证列举失败、无法选取样本对象时的原因，此时跳过 get/head 检查
+    pub sample_error: Option<String>,
+}
+
+const ANONYMOUS_TIMEOUT: Duration = Duration::from_secs(12);
+
+// 不带任何凭证发送一次请求，只看服务端是否放行，不重试也不跟随重定向
+async fn anonymous_check(cfg: &OssConfig, operation: &str, request: SignedRequest<'_>) -> Result<OssAnonymousCheck, String> {
+    let url = request_url(cfg, &request)?;
+    let mut check = OssAnonymousCheck {
+        operation: operation.to_string(),
+        url: url.to_string(),
+        status: None,
+        allowed: false,
+        code: None,
+        error: None,
+    };
+    let send = signed_client()?.request(request.method.clone(), url).headers(request.headers).send();
+    let response = match tokio::time::timeout(ANONYMOUS_TIMEOUT, send).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            check.error = Some(redact_error(e));
+            return Ok(check);
+        }
+        Err(_) => {
+            check.error = Some("请求超时".to_string());
+            return Ok(check);
+        }
+    };
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    check.status = Some(status);
+    check.code = xml::text(&body, "Code");
+    check.allowed = match operation {
+        // 部分厂商对拒绝的请求也返回 200 的错误页，列举需确认返回的是列表
+        "list" => status == 200 && body.contains("<ListBucketResult"),
+        _ => status == 200 || status == 206,
+    };
+    Ok(check)
+}
+
+// 检查单个存储桶能否在不带凭证的情况下被列举、下载或读取元数据
+#[command]
+pub async fn oss_check_anonymous_access(cfg: OssConfig, params: OssAnonymousAccessParams) -> Result<OssResult<OssAnonymousAccess>, String> {
+    let bucket = params.bucket.or_else(|| cfg.bucket.clone()).filter(|b| !b.is_empty()).ok_or_else(|| "Missing bucket name".to_string())?;
+    let mut target = bucket_config(&cfg, &bucket, None);
+
+    let mut sample_error = None;
+    let key = match params.key.filter(|k| !k.is_empty()) {
+        Some(key) => Some(key),
+        // 凭证没有列举权限时仍然做匿名 list 检查
+        None => match list_objects_page(&target, "", "", None, None, 10).await {
+            Ok(page) => {
+                if let Some(c) = page.correction {
+                    c.apply(&mut target);
+                }
+                page.value.items.into_iter().map(|o| o.key).find(|k| !k.ends_with('/'))
+            }
+            Err(e) => {
+                sample_error = Some(format!("列举样本对象失败，已跳过 get/head 检查: {}", e));
+                None
+            }
+        },
+    };
+
+    let mut checks = vec![
+        anonymous_check(
+            &target,
+            "list",
+            SignedRequest {
+                method: Method::GET,
+                key: None,
+                query: &[("list-type", "2"), ("max-keys", "1")],
+                headers: HeaderMap::new(),
+                body: Vec::new(),
+            },
+        )
+        .await?,
+    ];
+    if let Some(key) = key.as_deref() {
+        // 只取第一个字节，避免下载整个对象
+        let mut headers = HeaderMap::new();
+        headers.insert(RANGE, HeaderValue::from_static("bytes=0-0"));
+        let get = SignedRequest { method: Method::GET, key: Some(key), query: &[], headers, body: Vec::new() };
+        checks.push(anonymous_check(&target, "get", get).await?);
+        let head = SignedRequest { method: Method::HEAD, key: Some(key), query: &[], headers: HeaderMap::new(), body: Vec::new() };
+        checks.push(anonymous_check(&target, "head", head).await?);
+    }
+
+    let allowed_operations: Vec<String> = checks.iter().filter(|c| c.allowed).map(|c| c.operation.clone()).collect();
+    let message = if allowed_operations.is_empty() {
+        "匿名请求均被拒绝".to_string()
+    } else {
+        format!("匿名可执行: {}", allowed_operations.join(", "))
+    };
+    Ok(OssResult {
+        success: true,
+        message: Some(message),
+        data: Some(OssAnonymousAccess { bucket, key, checks, allowed_operations, sample_error }),
+        error: None,
+        correction: None,
+    })
+}
+
+#[command]
+pub async fn oss_check_anonymous_access_by_id(
+    vault: State<'_, VaultState>,
+    config_id: String,
+    params: OssAnonymousAccessParams,
+) -> Result<OssResult<OssAnonymousAccess>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    oss_check_anonymous_access(cfg, params).await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssCorsRule {
+    pub id: Option<String>,
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub expose_headers: Vec<String>,
+    pub max_age_seconds: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssLifecycleTransition {
+    pub days: Option<u32>,
+    pub date: Option<String>,
+    pub storage_class: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssLifecycleRule {
+    pub id: Option<String>,
+    // Enabled / Disabled
+    pub status: Option<String>,
+    pub prefix: Option<String>,
+    pub expiration_days: Option<u32>,
+    pub expiration_date: Option<String>,
+    pub noncurrent_expiration_days: Option<u32>,
+    pub abort_incomplete_upload_days: Option<u32>,
+    pub transitions: Vec<OssLifecycleTransition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssVersioning {
+    // Enabled / Suspended；从未开启过时为空
+    pub status: Option<String>,
+    pub mfa_delete: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssEncryption {
+    // AES256 / aws:kms / KMS / SM4 等
+    pub algorithm: Option<String>,
+    pub kms_key_id: Option<String>,
+    pub bucket_key_enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssLogging {
+    pub target_bucket: String,
+    pub target_prefix: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssWebsite {
+    pub index_document: Option<String>,
+    pub error_document: Option<String>,
+    // 设置后所有请求都重定向到该主机
+    pub redirect_all_to: Option<String>,
+    pub routing_rule_count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssReplicationRule {
+    pub id: Option<String>,
+    pub status: Option<String>,
+    pub prefix: Option<String>,
+    pub destination_bucket: Option<String>,
+    pub storage_class: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssReplication {
+    pub role: Option<String>,
+    pub rules: Vec<OssReplicationRule>,
+}
+
+// 各项配置未设置时为空；厂商不支持或读取失败的项记录在 errors 中，键为配置项名称
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssBucketDescription {
+    pub bucket: String,
+    pub cors: Option<Vec<OssCorsRule>>,
+    pub lifecycle: Option<Vec<OssLifecycleRule>>,
+    pub versioning: Option<OssVersioning>,
+    pub encryption: Option<OssEncryption>,
+    pub logging: Option<OssLogging>,
+    pub website: Option<OssWebsite>,
+    pub replication: Option<OssReplication>,
+    pub errors: BTreeMap<String, String>,
+}
+
+const DESCRIBE_SECTIONS: usize = 7;
+
+// 各厂商（AWS / 阿里云 / 腾讯云 / 华为云 / 七牛）表示“未配置”的错误码，大多沿用 AWS 的命名
+const MISSING_POLICY: &[&str] = &["NoSuchBucketPolicy"];
+const MISSING_PUBLIC_ACCESS_BLOCK: &[&str] = &["NoSuchPublicAccessBlockConfiguration"];
+const MISSING_CORS: &[&str] = &["NoSuchCORSConfiguration"];
+const MISSING_LIFECYCLE: &[&str] = &["NoSuchLifecycleConfiguration"];
+// 默认加密：AWS / 华为云、阿里云、腾讯云各不相同
+const MISSING_ENCRYPTION: &[&str] = &[
+    "ServerSideEncryptionConfigurationNotFoundError",
+    "NoSuchServerSideEncryptionRule",
+    "NoSuchEncryptionConfiguration",
+];
+const MISSING_WEBSITE: &[&str] = &["NoSuchWebsiteConfiguration"];
+const MISSING_REPLICATION: &[&str] = &["ReplicationConfigurationNotFoundError", "NoSuchReplicationRule"];
+
+fn texts(doc: &str, tag: &str) -> Vec<String> {
+    xml::blocks(doc, tag).into_iter().map(|s| xml::unescape(s.trim())).collect()
+}
+
+fn number(doc: &str, tag: &str) -> Option<u32> {
+    xml::text(doc, tag)?.parse().ok()
+}
+
+// 规则的前缀可能直接写在规则下，也可能在 <Filter> 中
+fn rule_prefix(rule: &str) -> Option<String> {
+    xml::blocks(rule, "Filter")
+        .first()
+        .and_then(|filter| xml::text(filter, "Prefix"))
+        .or_else(|| xml::text(rule, "Prefix"))
+}
+
+fn parse_cors(body: &str) -> Vec<OssCorsRule> {
+    xml::blocks(body, "CORSRule")
+        .into_iter()
+        .map(|rule| OssCorsRule {
+            id: xml::text(rule, "ID"),
+            allowed_origins: texts(rule, "AllowedOrigin"),
+            allowed_methods: texts(rule, "AllowedMethod"),
+            allowed_headers: texts(rule, "AllowedHeader"),
+            expose_headers: texts(rule, "ExposeHeader"),
+            max_age_seconds: number(rule, "MaxAgeSeconds"),
+        })
+        .collect()
+}
+
+fn parse_lifecycle(body: &str) -> Vec<OssLifecycleRule> {
+    xml::blocks(body, "Rule")
+        .into_iter()
+        .map(|rule| {
+            let expiration = xml::blocks(rule, "Expiration").first().copied().unwrap_or_default();
+            OssLifecycleRule {
+                id: xml::text(rule, "ID"),
+                status: xml::text(rule, "Status"),
+                prefix: rule_prefix(rule),
+                expiration_days: number(expiration, "Days"),
+                expiration_date: xml::text(expiration, "Date"),
+                noncurrent_expiration_days: xml::blocks(rule, "NoncurrentVersionExpiration")
+                    .first()
+                    .and_then(|e| number(e, "NoncurrentDays")),
+                abort_incomplete_upload_days: xml::blocks(rule, "AbortIncompleteMultipartUpload")
+                    .first()
+                    .and_then(|a| number(a, "DaysAfterInitiation")),
+                transitions: xml::blocks(rule, "Transition")
+                    .into_iter()
+                    .map(|t| OssLifecycleTransition {
+                        days: number(t, "Days"),
+                        date: xml::text(t, "Date"),
+                        storage_class: xml::text(t, "StorageClass"),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+fn parse_encryption(body: &str) -> OssEncryption {
+    OssEncryption {
+        algorithm: xml::text(body, "SSEAlgorithm"),
+        kms_key_id: xml::text(body, "KMSMasterKeyID"),
+        bucket_key_enabled: xml::text(body, "BucketKeyEnabled").map(|v| v.eq_ignore_ascii_case("true")),
+    }
+}
+
+// 未开启日志时返回空的 <BucketLoggingStatus/>
+fn parse_logging(body: &str) -> Option<OssLogging> {
+    Some(OssLogging { target_bucket: xml::text(body, "TargetBucket")?, target_prefix: xml::text(body, "TargetPrefix") })
+}
+
+fn parse_website(body: &str) -> OssWebsite {
+    let nested = |outer, inner| xml::blocks(body, outer).first().and_then(|block| xml::text(block, inner));
+    OssWebsite {
+        index_document: nested("IndexDocument", "Suffix"),
+        error_document: nested("ErrorDocument", "Key"),
+        redirect_all_to: nested("RedirectAllRequestsTo", "HostName"),
+        routing_rule_count: xml::blocks(body, "RoutingRule").len() as u32,
+    }
+}
+
+fn parse_replication(body: &str) -> OssReplication {
+    OssReplication {
+        role: xml::text(body, "Role"),
+        rules: xml::blocks(body, "Rule")
+            .into_iter()
+            .map(|rule| {
+                let destination = xml::blocks(rule, "Destination").first().copied().unwrap_or_default();
+                OssReplicationRule {
+                    id: xml::text(rule, "ID"),
+                    status: xml::text(rule, "Status"),
+                    prefix: rule_prefix(rule),
+                    destination_bucket: xml::text(destination, "Bucket"),
+                    storage_class: xml::text(destination, "StorageClass"),
+                }
+            })
+            .collect(),
+    }
+}
+
+// 读取一项配置，未设置时为 None，其余错误记入 errors
+async fn describe_section<T>(
+    cfg: &mut OssConfig,
+    correction: &mut Option<EndpointCorrection>,
+    errors: &mut BTreeMap<String, String>,
+    subresource: &str,
+    missing_codes: &[&str],
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> Option<T> {
+    let result = fetch_subresource(cfg, correction, None, subresource).await;
+    let (body, error) = optional_config(result, missing_codes);
+    if let Some(error) = error {
+        errors.insert(subresource.to_string(), error);
+    }
+    parse(&body?)
+}
+
+// 汇总存储桶的 CORS、生命周期、版本控制、默认加密、访问日志、静态网站与跨区域复制配置
+#[command]
+pub async fn oss_describe_bucket(cfg: OssConfig) -> Result<OssResult<OssBucketDescription>, String> {
+    let bucket = cfg.bucket.clone().ok_or_else(|| "Missing bucket name".to_string())?;
+    let mut cfg = cfg;
+    let mut correction = None;
+    let mut errors = BTreeMap::new();
+    let (c, e) = (&mut correction, &mut errors);
+
+    let cors = describe_section(&mut cfg, c, e, "cors", MISSING_CORS, |b| Some(parse_cors(b))).await;
+    let lifecycle = describe_section(&mut cfg, c, e, "lifecycle", MISSING_LIFECYCLE, |b| Some(parse_lifecycle(b))).await;
+    let versioning = describe_section(&mut cfg, c, e, "versioning", &[], |b| {
+        Some(OssVersioning { status: xml::text(b, "Status"), mfa_delete: xml::text(b, "MfaDelete") })
+    })
+    .await;
+    let encryption = describe_section(&mut cfg, c, e, "encryption", MISSING_ENCRYPTION, |b| Some(parse_encryption(b))).await;
+    let logging = describe_section(&mut cfg, c, e, "logging", &[], parse_logging).await;
+    let website = describe_section(&mut cfg, c, e, "website", MISSING_WEBSITE, |b| Some(parse_website(b))).await;
+    let replication = describe_section(&mut cfg, c, e, "replication", MISSING_REPLICATION, |b| Some(parse_replication(b))).await;
+
+    // 只有全部配置项都读取失败才视为失败
+    let success = errors.len() < DESCRIBE_SECTIONS;
+    let (message, error) = if success {
+        (Some("存储桶配置读取完成".to_string()), None)
+    } else {
+        (None, Some("存储桶配置读取失败".to_string()))
+    };
+    Ok(OssResult {
+        success,
+        data: Some(OssBucketDescription { bucket, cors, lifecycle, versioning, encryption, logging, website, replication, errors }),
+        message,
+        error,
+        correction,
+    })
+}
+
+#[command]
+pub async fn oss_describe_bucket_by_id(vault: State<'_, VaultState>, config_id: String) -> Result<OssResult<OssBucketDescription>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_describe_bucket(cfg).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(provider: &str, region: &str) -> OssConfig {
+        OssConfig {
+            provider: provider.to_string(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            region: Some(region.to_string()),
+            endpoint: None,
+            bucket: None,
+            session_token: None,
+            expiration: None,
+        }
+    }
+
+    fn create_body(cfg: &OssConfig, storage_class: Option<&str>) -> String {
+        let params = OssCreateBucketParams {
+            bucket: "b".to_string(),
+            region: None,
+            acl: Some("private".to_string()),
+            storage_class: storage_class.map(str::to_string),
+        };
+        let (_, body) = create_bucket_request(cfg, &params).unwrap();
+        String::from_utf8(body).unwrap()
+    }
+
+    #[test]
+    fn validates_bucket_names() {
+        assert!(validate_bucket_name("my-bucket-1250000000").is_ok());
+        assert!(validate_bucket_name("ab").is_err());
+        assert!(validate_bucket_name("-bucket").is_err());
+        assert!(validate_bucket_name("My_Bucket").is_err());
+    }
+
+    #[test]
+    fn builds_location_constraints_per_provider() {
+        assert_eq!(create_body(&config("aws", "us-east-1"), None), "");
+        assert_eq!(
+            create_body(&config("aws", "eu-west-1"), None),
+            "<CreateBucketConfiguration><LocationConstraint>eu-west-1</LocationConstraint></CreateBucketConfiguration>"
+        );
+        assert_eq!(
+            create_body(&config("aliyun", "oss-cn-shanghai"), Some("IA")),
+            "<CreateBucketConfiguration><LocationConstraint>oss-cn-shanghai</LocationConstraint><StorageClass>IA</StorageClass></CreateBucketConfiguration>"
+        );
+        assert_eq!(create_body(&config("tencent", "ap-guangzhou"), None), "");
+        assert!(create_body(&config("qiniu", "z2"), None).contains("<LocationConstraint>cn-south-1</LocationConstraint>"));
+        assert!(create_body(&config("huawei", "cn-north-4"), Some("WARM")).contains("cn-north-4"));
+
+        let params = OssCreateBucketParams { bucket: "b".to_string(), region: None, acl: None, storage_class: Some("IA".to_string()) };
+        assert!(create_bucket_request(&config("tencent", "ap-guangzhou"), &params).is_err());
+    }
+
+    #[test]
+    fn flags_public_acl_grants() {
+        let body = r#"<AccessControlPolicy><Owner><ID>owner</ID></Owner><AccessControlList>
+            <Grant><Grantee xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="CanonicalUser"><ID>owner</ID></Grantee><Permission>FULL_CONTROL</Permission></Grant>
+            <Grant><Grantee xsi:type="Group"><URI>http://acs.amazonaws.com/groups/global/AllUsers</URI></Grantee><Permission>READ</Permission></Grant>
+            <Grant><Grantee xsi:type="Group"><URI>http://cam.qcloud.com/groups/global/AuthenticatedUsers</URI></Grantee><Permission>WRITE</Permission></Grant>
+        </AccessControlList></AccessControlPolicy>"#;
+        let acl = parse_acl(body);
+        assert_eq!(acl.owner_id.as_deref(), Some("owner"));
+        assert_eq!(acl.grants.len(), 3);
+        let risks: Vec<&str> = acl_risks(&acl).into_iter().map(|(r, _)| r).collect();
+        assert_eq!(risks, vec![RISK_PUBLIC_READ, RISK_AUTHENTICATED]);
+    }
+
+    #[test]
+    fn flags_anonymous_policy_statements() {
+        let policy: Value = serde_json::from_str(
+            r#"{"Statement":[
+                {"Effect":"Allow","Principal":{"AWS":["*"]},"Action":"s3:GetObject"},
+                {"Effect":"Allow","Principal":"*","Action":["s3:PutObject"],"Condition":{"IpAddress":{}}},
+                {"Effect":"Allow","Principal":{"AWS":"arn:aws:iam::1:root"},"Action":"s3:*"},
+                {"Effect":"Deny","Principal":"*","Action":"s3:*"}
+            ]}"#,
+        )
+        .unwrap();
+        let risks = policy_risks(&policy);
+        assert_eq!(risks.len(), 2);
+        assert_eq!(risks[0].0, RISK_PUBLIC_READ);
+        assert_eq!(risks[1], (RISK_PUBLIC_WRITE, "s3:PutObject（带条件）".to_string()));
+    }
+
+    #[test]
+    fn public_access_block_masks_risks() {
+        let acl = parse_acl(
+            "<Grant><Grantee><URI>http://acs.amazonaws.com/groups/global/AllUsers</URI></Grantee><Permission>FULL_CONTROL</Permission></Grant>",
+        );
+        let mut report = OssBucketSecurity {
+            bucket: "b".to_string(),
+            acl: Some(acl),
+            policy: None,
+            policy_error: None,
+            public_access_block: None,
+            public_access_block_error: None,
+            object_acls: Vec::new(),
+            sample_error: None,
+            risks: Vec::new(),
+            verdict: String::new(),
+            findings: Vec::new(),
+        };
+        summarize_security(&mut report);
+        assert_eq!(report.verdict, RISK_PUBLIC_WRITE);
+        assert_eq!(report.risks, vec![RISK_PUBLIC_WRITE, RISK_PUBLIC_READ]);
+
+        report.public_access_block = Some(parse_public_access_block("<IgnorePublicAcls>true</IgnorePublicAcls>"));
+        summarize_security(&mut report);
+        assert_eq!(report.verdict, VERDICT_PRIVATE);
+        assert_eq!(report.findings.len(), 2);
+
+        report.policy_error = Some("AccessDenied".to_string());
+        summarize_security(&mut report);
+        assert_eq!(report.verdict, VERDICT_UNKNOWN);
+        assert_eq!(report.findings.len(), 3);
+    }
+
+    #[test]
+    fn treats_provider_missing_codes_as_unset() {
+        let aliyun = Err::<(), _>("<Error><Code>NoSuchServerSideEncryptionRule</Code></Error>".to_string());
+        assert_eq!(optional_config(aliyun, MISSING_ENCRYPTION), (None, None));
+        let tencent = Err::<(), _>("<Error><Code>ReplicationConfigurationnotFoundError</Code></Error>".to_string());
+        assert_eq!(optional_config(tencent, MISSING_REPLICATION), (None, None));
+        let denied = Err::<(), _>("<Error><Code>AccessDenied</Code></Error>".to_string());
+        assert!(optional_config(denied, MISSING_ENCRYPTION).1.is_some());
+    }
+
+    #[test]
+    fn parses_bucket_configuration() {
+        let cors = parse_cors(
+            "<CORSConfiguration><CORSRule><AllowedOrigin>*</AllowedOrigin><AllowedMethod>GET</AllowedMethod><AllowedMethod>PUT</AllowedMethod><MaxAgeSeconds>600</MaxAgeSeconds></CORSRule></CORSConfiguration>",
+        );
+        assert_eq!(cors[0].allowed_methods, vec!["GET", "PUT"]);
+        assert_eq!(cors[0].max_age_seconds, Some(600));
+
+        let lifecycle = parse_lifecycle(
+            "<LifecycleConfiguration><Rule><ID>logs</ID><Filter><Prefix>logs/</Prefix></Filter><Status>Enabled</Status>\
+             <Transition><Days>30</Days><StorageClass>STANDARD_IA</StorageClass></Transition>\
+             <Expiration><Days>365</Days></Expiration>\
+             <AbortIncompleteMultipartUpload><DaysAfterInitiation>7</DaysAfterInitiation></AbortIncompleteMultipartUpload></Rule></LifecycleConfiguration>",
+        );
+        assert_eq!(lifecycle[0].prefix.as_deref(), Some("logs/"));
+        assert_eq!(lifecycle[0].expiration_days, Some(365));
+        assert_eq!(lifecycle[0].abort_incomplete_upload_days, Some(7));
+        assert_eq!(lifecycle[0].transitions[0].storage_class.as_deref(), Some("STANDARD_IA"));
+
+        assert!(parse_logging("<BucketLoggingStatus/>").is_none());
+        let website = parse_website(
+            "<WebsiteConfiguration><IndexDocument><Suffix>index.html</Suffix></IndexDocument><RoutingRules><RoutingRule/><RoutingRule/></RoutingRules></WebsiteConfiguration>",
+        );
+        assert_eq!(website.index_document.as_deref(), Some("index.html"));
+        assert_eq!(website.routing_rule_count, 2);
+
+        let replication = parse_replication(
+            "<ReplicationConfiguration><Role>arn</Role><Rule><Status>Enabled</Status><Prefix>a/</Prefix><Destination><Bucket>arn:aws:s3:::dst</Bucket></Destination></Rule></ReplicationConfiguration>",
+        );
+        assert_eq!(replication.rules[0].destination_bucket.as_deref(), Some("arn:aws:s3:::dst"));
+        assert_eq!(replication.rules[0].prefix.as_deref(), Some("a/"));
+    }
+}
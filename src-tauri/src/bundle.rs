@@ -0,0 +1,388 @@
+use crate::storage::{atomic_write, list_data_files};
+use crate::vault::{derive_key, open, seal, verify_master_password, Envelope, KdfParams, VaultState};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use tauri::api::dialog::blocking::FileDialogBuilder;
+use tauri::{command, State};
+use zeroize::Zeroizing;
+
+const BUNDLE_FORMAT: &str = "sensitive-info-tool-bundle";
+const BUNDLE_VERSION: u32 = 1;
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+// 明文头部，整体作为附加认证数据参与加密，任何改动都会导致解密失败
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct BundleHeader {
+    format: String,
+    version: u32,
+    created_at: String,
+    record_count: usize,
+    kdf: KdfParams,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct BundleFile {
+    header: BundleHeader,
+    payload: Envelope,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct BundleRecord {
+    key: String,
+    sha256: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    // 按记录 id 与本地数据合并
+    Merge,
+    // 用导入包中的内容整体覆盖同名数据键
+    Replace,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportConflict {
+    pub key: String,
+    pub id: Option<String>,
+    // kept_local / took_incoming
+    pub resolution: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct KeyImportSummary {
+    pub key: String,
+    // created / replaced / merged / unchanged
+    pub action: String,
+    pub added: usize,
+    pub updated: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportFailure {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportFailure {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportReport {
+    // 导出包写入的本地路径
+    pub path: String,
+    pub exported: Vec<String>,
+    // 无法解密的数据键不进入导出包
+    pub failed: Vec<ExportFailure>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportReport {
+    pub mode: ImportMode,
+    pub created_at: String,
+    pub keys: Vec<KeyImportSummary>,
+    pub conflicts: Vec<ImportConflict>,
+    pub failed: Vec<ImportFailure>,
+}
+
+fn sha256_hex(data: &str) -> String {
+    hex::encode(Sha256::digest(data.as_bytes()))
+}
+
+fn header_aad(header: &BundleHeader) -> Result<String, String> {
+    serde_json::to_string(header).map_err(|e| e.to_string())
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("导出口令至少需要 {} 个字符", MIN_PASSPHRASE_LEN));
+    }
+    Ok(())
+}
+
+fn seal_bundle(records: &[BundleRecord], passphrase: &str) -> Result<String, String> {
+    let header = BundleHeader {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        created_at: Utc::now().to_rfc3339(),
+        record_count: records.len(),
+        kdf: KdfParams::generate(),
+    };
+    let key = derive_key(passphrase, &header.kdf)?;
+    let plain = Zeroizing::new(serde_json::to_vec(records).map_err(|e| e.to_string())?);
+    let payload = seal(&key, &header_aad(&header)?, &plain)?;
+    serde_json::to_string_pretty(&BundleFile { header, payload }).map_err(|e| e.to_string())
+}
+
+fn open_bundle(bundle: &str, passphrase: &str) -> Result<(BundleHeader, Vec<BundleRecord>), String> {
+    let file: BundleFile = serde_json::from_str(bundle).map_err(|_| "不是有效的导出包".to_string())?;
+    let header = file.header;
+    if header.format != BUNDLE_FORMAT {
+        return Err("不是有效的导出包".to_string());
+    }
+    if header.version != BUNDLE_VERSION {
+        return Err(format!("不支持的导出包版本: {}", header.version));
+    }
+
+    header.kdf.check_limits()?;
+    let key = derive_key(passphrase, &header.kdf)?;
+    let plain = open(&key, &header_aad(&header)?, &file.payload)
+        .map_err(|_| "导出口令错误或导出包已损坏".to_string())?;
+    let records: Vec<BundleRecord> =
+        serde_json::from_slice(&plain).map_err(|e| format!("导出包内容格式错误: {}", e))?;
+
+    if records.len() != header.record_count {
+        return Err("导出包记录数量与头部不一致".to_string());
+    }
+    for record in &records {
+        if sha256_hex(&record.data) != record.sha256 {
+            return Err(format!("导出包中 {} 的校验和不匹配", record.key));
+        }
+    }
+    Ok((header, records))
+}
+
+fn record_id(item: &Value) -> Option<String> {
+    match item.get("id")? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn updated_at(item: &Value) -> &str {
+    item.get("updatedAt").and_then(|v| v.as_str()).unwrap_or("")
+}
+
+// 按记录 id 合并两份数据：本地没有的直接添加，同 id 且内容不同视为冲突，保留 updatedAt 较新的一方
+fn merge_values(key: &str, local: Value, incoming: Value) -> (Value, KeyImportSummary, Vec<ImportConflict>) {
+    let mut summary = KeyImportSummary { key: key.to_string(), action: "unchanged".to_string(), ..Default::default() };
+    let mut conflicts = Vec::new();
+
+    let (Value::Array(mut merged), Value::Array(incoming_items)) = (local.clone(), incoming.clone()) else {
+        // 非列表数据无法按 id 合并，内容不同时保留本地并记为冲突
+        if local != incoming {
+            conflicts.push(ImportConflict { key: key.to_string(), id: None, resolution: "kept_local".to_string() });
+        }
+        return (local, summary, conflicts);
+    };
+
+    let mut index: HashMap<String, usize> = merged
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| record_id(item).map(|id| (id, i)))
+        .collect();
+
+    for item in incoming_items {
+        match record_id(&item) {
+            Some(id) => match index.get(&id) {
+                None => {
+                    index.insert(id, merged.len());
+                    merged.push(item);
+                    summary.added += 1;
+                }
+                Some(&i) if merged[i] == item => {}
+                Some(&i) => {
+                    let take_incoming = updated_at(&item) > updated_at(&merged[i]);
+                    if take_incoming {
+                        merged[i] = item;
+                        summary.updated += 1;
+                    }
+                    conflicts.push(ImportConflict {
+                        key: key.to_string(),
+                        id: Some(id),
+                        resolution: if take_incoming { "took_incoming" } else { "kept_local" }.to_string(),
+                    });
+                }
+            },
+            None => {
+                if !merged.contains(&item) {
+                    merged.push(item);
+                    summary.added += 1;
+                }
+            }
+        }
+    }
+
+    if summary.added > 0 || summary.updated > 0 {
+        summary.action = "merged".to_string();
+    }
+    (Value::Array(merged), summary, conflicts)
+}
+
+fn import_record(
+    state: &VaultState,
+    record: &BundleRecord,
+    mode: ImportMode,
+    conflicts: &mut Vec<ImportConflict>,
+) -> Result<KeyImportSummary, String> {
+    let existing = state.load_record(&record.key)?;
+    let Some(existing) = existing else {
+        state.store_record(&record.key, &record.data)?;
+        return Ok(KeyImportSummary { key: record.key.clone(), action: "created".to_string(), ..Default::default() });
+    };
+
+    if mode == ImportMode::Replace {
+        if existing.as_str() == record.data {
+            return Ok(KeyImportSummary { key: record.key.clone(), action: "unchanged".to_string(), ..Default::default() });
+        }
+        state.store_record(&record.key, &record.data)?;
+        return Ok(KeyImportSummary { key: record.key.clone(), action: "replaced".to_string(), ..Default::default() });
+    }
+
+    let local: Value = serde_json::from_str(&existing).map_err(|e| format!("本地数据格式错误: {}", e))?;
+    let incoming: Value = serde_json::from_str(&record.data).map_err(|e| format!("导入数据格式错误: {}", e))?;
+    let (merged, summary, mut key_conflicts) = merge_values(&record.key, local, incoming);
+    if summary.action == "merged" {
+        let text = Zeroizing::new(serde_json::to_string(&merged).map_err(|e| e.to_string())?);
+        state.store_record(&record.key, &text)?;
+    }
+    conflicts.append(&mut key_conflicts);
+    Ok(summary)
+}
+
+// 将所有数据键解密后打包，并用导出口令重新加密；单个键读取失败时跳过并计入失败列表。
+// 导出包含完整凭证，需重新输入主密码，且由后端弹出保存对话框直接写文件，导出包不经过前端；
+// 用户取消保存时返回 None
+#[command]
+pub async fn export_bundle(
+    state: State<'_, VaultState>,
+    password: String,
+    passphrase: String,
+) -> Result<Option<ExportReport>, String> {
+    let password = Zeroizing::new(password);
+    let passphrase = Zeroizing::new(passphrase);
+    check_passphrase(&passphrase)?;
+    verify_master_password(&password)?;
+
+    let file_name = format!("sensitive-info-{}.bundle.json", Utc::now().format("%Y%m%d-%H%M%S"));
+    let Some(path) = FileDialogBuilder::new()
+        .set_title("保存导出包")
+        .set_file_name(&file_name)
+        .add_filter("导出包", &["json"])
+        .save_file()
+    else {
+        return Ok(None);
+    };
+
+    let mut records = Vec::new();
+    let mut failed = Vec::new();
+    for key in list_data_files()? {
+        match state.load_record(&key) {
+            Ok(Some(data)) => records.push(BundleRecord { sha256: sha256_hex(&data), data: data.to_string(), key }),
+            Ok(None) => {}
+            Err(error) => failed.push(ExportFailure { key, error }),
+        }
+    }
+    if records.is_empty() && !failed.is_empty() {
+        return Err(format!("{} 个数据键均无法导出: {}", failed.len(), failed[0].error));
+    }
+    let bundle = Zeroizing::new(seal_bundle(&records, &passphrase)?);
+    atomic_write(&path, bundle.as_bytes())?;
+    Ok(Some(ExportReport {
+        path: path.to_string_lossy().to_string(),
+        exported: records.into_iter().map(|r| r.key).collect(),
+        failed,
+    }))
+}
+
+// 导入导出包，merge 按记录 id 合并，replace 覆盖同名数据键（覆盖前的版本会进入备份）
+#[command]
+pub fn import_bundle(
+    state: State<'_, VaultState>,
+    bundle: String,
+    passphrase: String,
+    mode: ImportMode,
+) -> Result<ImportReport, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    let (header, records) = open_bundle(&bundle, &passphrase)?;
+
+    let mut report = ImportReport {
+        mode,
+        created_at: header.created_at,
+        keys: Vec::new(),
+        conflicts: Vec::new(),
+        failed: Vec::new(),
+    };
+    // 导入包来自其他机器，读写记录时键同样会经过数据目录校验，非法键计入失败列表
+    for record in &records {
+        match import_record(&state, record, mode, &mut report.conflicts) {
+            Ok(summary) => report.keys.push(summary),
+            Err(error) => report.failed.push(ImportFailure { key: record.key.clone(), error }),
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(key: &str, data: &str) -> BundleRecord {
+        BundleRecord { key: key.to_string(), sha256: sha256_hex(data), data: data.to_string() }
+    }
+
+    #[test]
+    fn bundle_round_trip() {
+        let records = vec![record("sensitive_wechat", r#"[{"id":"1"}]"#)];
+        let bundle = seal_bundle(&records, "correct horse").unwrap();
+        let (header, opened) = open_bundle(&bundle, "correct horse").unwrap();
+        assert_eq!(header.record_count, 1);
+        assert_eq!(opened[0].data, records[0].data);
+        assert!(open_bundle(&bundle, "wrong passphrase").is_err());
+    }
+
+    #[test]
+    fn oversized_kdf_params_are_rejected() {
+        let bundle = seal_bundle(&[record("k", "[]")], "correct horse").unwrap();
+        let mut file: Value = serde_json::from_str(&bundle).unwrap();
+        file["header"]["kdf"]["m_cost"] = json!(u32::MAX);
+        let error = open_bundle(&file.to_string(), "correct horse").unwrap_err();
+        assert!(error.contains("KDF 参数超出允许范围"));
+    }
+
+    #[test]
+    fn tampered_header_is_rejected() {
+        let bundle = seal_bundle(&[record("k", "[]")], "correct horse").unwrap();
+        let mut file: Value = serde_json::from_str(&bundle).unwrap();
+        file["header"]["created_at"] = json!("2000-01-01T00:00:00+00:00");
+        assert!(open_bundle(&file.to_string(), "correct horse").is_err());
+    }
+
+    #[test]
+    fn merge_adds_new_ids_and_reports_conflicts() {
+        let local = json!([
+            {"id": "a", "name": "local-a", "updatedAt": "2024-01-02"},
+            {"id": "b", "name": "local-b", "updatedAt": "2024-01-02"}
+        ]);
+        let incoming = json!([
+            {"id": "a", "name": "remote-a", "updatedAt": "2024-01-01"},
+            {"id": "b", "name": "remote-b", "updatedAt": "2024-01-03"},
+            {"id": "c", "name": "remote-c", "updatedAt": "2024-01-01"}
+        ]);
+        let (merged, summary, conflicts) = merge_values("k", local, incoming);
+        assert_eq!(summary.action, "merged");
+        assert_eq!((summary.added, summary.updated), (1, 1));
+        assert_eq!(merged[0]["name"], "local-a");
+        assert_eq!(merged[1]["name"], "remote-b");
+        assert_eq!(merged[2]["name"], "remote-c");
+        let resolutions: Vec<_> = conflicts.iter().map(|c| (c.id.clone().unwrap(), c.resolution.as_str())).collect();
+        assert_eq!(resolutions, vec![("a".to_string(), "kept_local"), ("b".to_string(), "took_incoming")]);
+    }
+
+    #[test]
+    fn merge_identical_data_is_unchanged() {
+        let data = json!([{"id": "a"}]);
+        let (_, summary, conflicts) = merge_values("k", data.clone(), data);
+        assert_eq!(summary.action, "unchanged");
+        assert!(conflicts.is_empty());
+    }
+}
@@ -0,0 +1,406 @@
+use crate::logging::{redact, redact_error};
+use crate::oss::{execute_on_bucket, load_oss_config, remember_correction, OssConfig, OssResult, RetryPolicy};
+use crate::storage::{atomic_write, create_private_dir, data_dir};
+use crate::transfer::{ActiveTransfers, TransferSignal};
+use crate::vault::VaultState;
+use chrono::Utc;
+use md5::Md5;
+use reqwest::header::{HeaderMap, IF_RANGE, RANGE};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::{command, State, Window};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+// 下载断点记录目录（位于数据目录下，不含任何凭证）
+const DOWNLOAD_DIR_NAME: &str = "downloads";
+const DOWNLOAD_PROGRESS_EVENT: &str = "oss-download-progress";
+// 未完成的下载先写入同目录下的临时文件，完成并校验后再改名
+const PARTIAL_SUFFIX: &str = ".part";
+
+const HEAD_POLICY: RetryPolicy = RetryPolicy::new(12, "请求超时，请检查网络或 Endpoint 配置");
+const PRESIGN_POLICY: RetryPolicy = RetryPolicy::new(12, "请求超时，请检查网络或 Endpoint 配置");
+// 预签名链接只在本次下载中使用
+const PRESIGN_EXPIRY_SECS: u32 = 3600;
+// 两次收到数据之间的最长等待时间
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(60);
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+const DOWNLOAD_CANCELLED: &str = "下载已取消";
+const DOWNLOAD_PAUSED: &str = "下载已暂停";
+
+// CRC-64/XZ（ECMA-182 反射多项式），阿里云 OSS 与腾讯云 COS 返回的 crc64ecma 即此算法
+const CRC64_POLY: u64 = 0xC96C_5795_D787_0F42;
+const CRC64_HEADERS: [&str; 2] = ["x-oss-hash-crc64ecma", "x-cos-hash-crc64ecma"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssDownloadPathParams {
+    pub key: String,
+    pub local_path: String,
+    // 本地已存在同名文件时是否覆盖
+    pub overwrite: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadProgress {
+    pub key: String,
+    pub local_path: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadSummary {
+    pub local_path: String,
+    pub bytes: u64,
+    // crc64 / md5；分片上传的对象没有可校验的 ETag 时为空
+    pub verified_by: Option<String>,
+}
+
+// 下载断点：远端 ETag 与大小不变时才续传
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct DownloadCheckpoint {
+    bucket: String,
+    key: String,
+    local_path: String,
+    etag: String,
+    total_bytes: u64,
+    created_at: String,
+}
+
+fn download_dir() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join(DOWNLOAD_DIR_NAME))
+}
+
+fn checkpoint_path(local_path: &str) -> Result<PathBuf, String> {
+    let name = hex::encode(Sha256::digest(local_path.as_bytes()));
+    Ok(download_dir()?.join(format!("{}.json", name)))
+}
+
+fn save_checkpoint(checkpoint: &DownloadCheckpoint) -> Result<(), String> {
+    create_private_dir(&download_dir()?)?;
+    let text = serde_json::to_string_pretty(checkpoint).map_err(|e| e.to_string())?;
+    atomic_write(&checkpoint_path(&checkpoint.local_path)?, text.as_bytes())
+}
+
+fn load_checkpoint(local_path: &str) -> Option<DownloadCheckpoint> {
+    let text = fs::read_to_string(checkpoint_path(local_path).ok()?).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_owned();
+    name.push(PARTIAL_SUFFIX);
+    PathBuf::from(name)
+}
+
+// 放弃下载：删除临时文件与断点
+pub(crate) fn discard_partial(local_path: &str) {
+    let _ = fs::remove_file(partial_path(Path::new(local_path)));
+    if let Ok(path) = checkpoint_path(local_path) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn crc64_table() -> &'static [u64; 256] {
+    static TABLE: OnceLock<[u64; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0u64; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let mut crc = i as u64;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 { (crc >> 1) ^ CRC64_POLY } else { crc >> 1 };
+            }
+            *entry = crc;
+        }
+        table
+    })
+}
+
+// 可分段累加：crc64_update(crc64_update(0, a), b) == crc64_update(0, a ++ b)
+fn crc64_update(crc: u64, data: &[u8]) -> u64 {
+    let table = crc64_table();
+    let mut crc = !crc;
+    for &b in data {
+        crc = table[((crc ^ b as u64) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+fn crc64_header(headers: &HeaderMap) -> Option<u64> {
+    CRC64_HEADERS
+        .iter()
+        .find_map(|name| headers.get(*name)?.to_str().ok()?.trim().parse().ok())
+}
+
+// 单次上传对象的 ETag 即内容 MD5；分片上传（带 -N 后缀）或 KMS 加密的对象不适用
+fn md5_etag(etag: &str, sse: Option<&str>) -> Option<String> {
+    let etag = etag.trim_matches('"').to_ascii_lowercase();
+    if sse.is_some_and(|s| s.contains("kms")) {
+        return None;
+    }
+    (etag.len() == 32 && etag.chars().all(|c| c.is_ascii_hexdigit())).then_some(etag)
+}
+
+// 优先用服务端 CRC64 校验，其次用 ETag 中的 MD5
+fn verify_file(path: &Path, crc64: Option<u64>, md5: Option<&str>) -> Result<Option<String>, String> {
+    if crc64.is_none() && md5.is_none() {
+        return Ok(None);
+    }
+    let mut file = fs::File::open(path).map_err(|e| format!("读取下载文件失败: {}", e))?;
+    let mut crc = 0u64;
+    let mut hasher = Md5::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| format!("读取下载文件失败: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        if crc64.is_some() {
+            crc = crc64_update(crc, &buf[..n]);
+        } else {
+            hasher.update(&buf[..n]);
+        }
+    }
+    match (crc64, md5) {
+        (Some(expected), _) if expected != crc => Err("CRC64 校验失败，文件可能已损坏，请重新下载".to_string()),
+        (Some(_), _) => Ok(Some("crc64".to_string())),
+        (None, Some(expected)) if hex::encode(hasher.finalize()) != expected => {
+            Err("MD5 校验失败，文件可能已损坏，请重新下载".to_string())
+        }
+        _ => Ok(Some("md5".to_string())),
+    }
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    // 预签名链接绑定了 Host，不跟随重定向
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(12))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(redact_error)
+}
+
+fn io_error(e: std::io::Error) -> String {
+    format!("写入本地文件失败: {}", e)
+}
+
+// 流式下载到本地文件，支持断点续传；on_progress 按固定间隔调用。
+// signal 被暂停时保留已下载部分，被取消时删除临时文件
+pub(crate) async fn download_to_path(
+    cfg: &OssConfig,
+    params: &OssDownloadPathParams,
+    active: &ActiveTransfers,
+    signal: &TransferSignal,
+    on_progress: impl Fn(&DownloadProgress) + Send + Sync,
+) -> Result<OssResult<DownloadSummary>, String> {
+    let dest = PathBuf::from(&params.local_path);
+    if dest.exists() && !params.overwrite.unwrap_or(false) {
+        return Err(format!("本地文件已存在: {}", params.local_path));
+    }
+    let part_path = partial_path(&dest);
+    let bucket_name = cfg.bucket.clone().ok_or_else(|| "Missing bucket name".to_string())?;
+    let key = &params.key;
+    let mut cfg = cfg.clone();
+
+    // 先 HEAD 获取 ETag 与大小，同时完成重定向修正
+    let head = execute_on_bucket(&cfg, HEAD_POLICY, |bucket| async move { bucket.head_object(key).await }).await?;
+    if let Some(c) = &head.correction {
+        c.apply(&mut cfg);
+    }
+    let (head_result, _) = head.value;
+    let etag = head_result.e_tag.clone().unwrap_or_default();
+    let total = head_result.content_length.unwrap_or(0).max(0) as u64;
+
+    let resumable = load_checkpoint(&params.local_path).is_some_and(|cp| {
+        cp.bucket == bucket_name && cp.key == params.key && cp.etag == etag && cp.total_bytes == total
+    });
+    let mut offset = if resumable { fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0).min(total) } else { 0 };
+    save_checkpoint(&DownloadCheckpoint {
+        bucket: bucket_name,
+        key: params.key.clone(),
+        local_path: params.local_path.clone(),
+        etag: etag.clone(),
+        total_bytes: total,
+        created_at: Utc::now().to_rfc3339(),
+    })?;
+
+    let _guard = active.register(&params.local_path, signal);
+    let progress = |downloaded_bytes| DownloadProgress {
+        key: params.key.clone(),
+        local_path: params.local_path.clone(),
+        downloaded_bytes,
+        total_bytes: total,
+    };
+    let mut crc64 = None;
+
+    if offset < total || total == 0 {
+        let url = execute_on_bucket(&cfg, PRESIGN_POLICY, |bucket| async move {
+            bucket.presign_get(key, PRESIGN_EXPIRY_SECS, None).await
+        })
+        .await?
+        .value;
+        let mut request = http_client()?.get(&url);
+        if offset > 0 {
+            // If-Range：对象已变化时服务端返回完整内容而不是片段
+            request = request.header(RANGE, format!("bytes={}-", offset));
+            if !etag.is_empty() {
+                request = request.header(IF_RANGE, &etag);
+            }
+        }
+        let mut response = request.send().await.map_err(redact_error)?;
+        match response.status().as_u16() {
+            206 => {}
+            200 => offset = 0,
+            status => {
+                let body = response.text().await.unwrap_or_default();
+                return Err(redact(&format!("下载失败: HTTP {} {}", status, body)));
+            }
+        }
+        crc64 = crc64_header(response.headers());
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(&part_path)
+            .await
+            .map_err(io_error)?;
+        file.set_len(offset).await.map_err(io_error)?;
+        file.seek(SeekFrom::Start(offset)).await.map_err(io_error)?;
+        on_progress(&progress(offset));
+
+        let mut downloaded = offset;
+        let mut last_emit = Instant::now();
+        loop {
+            if signal.is_paused() {
+                file.flush().await.map_err(io_error)?;
+                return Err(DOWNLOAD_PAUSED.to_string());
+            }
+            if signal.is_cancelled() {
+                drop(file);
+                discard_partial(&params.local_path);
+                return Err(DOWNLOAD_CANCELLED.to_string());
+            }
+            let chunk = tokio::time::timeout(CHUNK_TIMEOUT, response.chunk())
+                .await
+                .map_err(|_| "下载超时，已保存进度，重新下载时将从断点继续".to_string())?
+                .map_err(|e| format!("{}，已保存进度，重新下载时将从断点继续", redact_error(e)))?;
+            let Some(chunk) = chunk else { break };
+            file.write_all(&chunk).await.map_err(io_error)?;
+            downloaded += chunk.len() as u64;
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                on_progress(&progress(downloaded));
+                last_emit = Instant::now();
+            }
+        }
+        file.flush().await.map_err(io_error)?;
+        file.sync_all().await.map_err(io_error)?;
+        if total > 0 && downloaded != total {
+            return Err(format!("下载不完整: {}/{} 字节，重新下载时将从断点继续", downloaded, total));
+        }
+    }
+    on_progress(&progress(total));
+
+    let md5 = md5_etag(&etag, head_result.server_side_encryption.as_deref());
+    let verify_path = part_path.clone();
+    let verified_by = tokio::task::spawn_blocking(move || verify_file(&verify_path, crc64, md5.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .inspect_err(|_| discard_partial(&params.local_path))?;
+
+    if dest.exists() {
+        fs::remove_file(&dest).map_err(io_error)?;
+    }
+    fs::rename(&part_path, &dest).map_err(io_error)?;
+    discard_partial(&params.local_path);
+
+    Ok(OssResult {
+        success: true,
+        data: Some(DownloadSummary { local_path: params.local_path.clone(), bytes: total, verified_by }),
+        message: Some("文件下载成功".to_string()),
+        error: None,
+        correction: head.correction,
+    })
+}
+
+#[command]
+pub async fn oss_download_to_path(
+    window: Window,
+    active: State<'_, ActiveTransfers>,
+    cfg: OssConfig,
+    params: OssDownloadPathParams,
+) -> Result<OssResult<DownloadSummary>, String> {
+    download_to_path(&cfg, &params, &active, &TransferSignal::default(), |progress| {
+        let _ = window.emit(DOWNLOAD_PROGRESS_EVENT, progress.clone());
+    })
+    .await
+}
+
+#[command]
+pub async fn oss_download_to_path_by_id(
+    window: Window,
+    vault: State<'_, VaultState>,
+    active: State<'_, ActiveTransfers>,
+    config_id: String,
+    params: OssDownloadPathParams,
+) -> Result<OssResult<DownloadSummary>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = download_to_path(&cfg, &params, &active, &TransferSignal::default(), |progress| {
+        let _ = window.emit(DOWNLOAD_PROGRESS_EVENT, progress.clone());
+    })
+    .await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+// 取消下载并删除未完成的临时文件
+#[command]
+pub fn oss_cancel_download(active: State<'_, ActiveTransfers>, local_path: String) -> Result<(), String> {
+    if !active.cancel(&local_path) {
+        discard_partial(&local_path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc64_matches_reference() {
+        // CRC-64/XZ 标准校验值
+        assert_eq!(crc64_update(0, b"123456789"), 0x995D_C9BB_DF19_39FA);
+        let chained = crc64_update(crc64_update(0, b"1234"), b"56789");
+        assert_eq!(chained, 0x995D_C9BB_DF19_39FA);
+    }
+
+    #[test]
+    fn md5_etag_only_for_single_part_objects() {
+        assert_eq!(
+            md5_etag("\"D41D8CD98F00B204E9800998ECF8427E\"", None).as_deref(),
+            Some("d41d8cd98f00b204e9800998ecf8427e")
+        );
+        assert_eq!(md5_etag("\"d41d8cd98f00b204e9800998ecf8427e-3\"", None), None);
+        assert_eq!(md5_etag("d41d8cd98f00b204e9800998ecf8427e", Some("aws:kms")), None);
+    }
+
+    #[test]
+    fn verifies_downloaded_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("f");
+        fs::write(&path, b"123456789").unwrap();
+        assert_eq!(verify_file(&path, Some(0x995D_C9BB_DF19_39FA), None).unwrap().as_deref(), Some("crc64"));
+        assert!(verify_file(&path, Some(1), None).is_err());
+        assert_eq!(
+            verify_file(&path, None, Some("25f9e794323b453885f5181f1b624d0b")).unwrap().as_deref(),
+            Some("md5")
+        );
+        assert!(verify_file(&path, None, Some("00000000000000000000000000000000")).is_err());
+        assert_eq!(verify_file(&path, None, None).unwrap(), None);
+    }
+}
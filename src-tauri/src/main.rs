@@ -1,113 +1,69 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod bucket;
+mod bundle;
+mod download;
+mod logging;
+mod migrate;
+mod object;
 mod oss;
+mod queue;
+mod records;
+mod settings;
+mod sigv4;
+mod storage;
+mod transfer;
+mod upload;
+mod vault;
+mod xml;
 
+use bucket::*;
+use bundle::*;
+use download::*;
+use logging::init_logging;
+use migrate::*;
+use object::*;
 use oss::*;
-use std::path::PathBuf;
-use std::fs;
-use std::io::Write;
+use queue::*;
+use settings::*;
+use storage::*;
+use transfer::*;
+use upload::*;
+use vault::*;
+use std::time::Duration;
 use tauri::Manager;
 
-// 获取用户家目录
-#[tauri::command]
-fn get_home_dir() -> Result<String, String> {
-    match dirs::home_dir() {
-        Some(path) => Ok(path.to_string_lossy().to_string()),
-        None => Err("无法获取用户家目录".to_string()),
-    }
-}
-
-// 获取数据目录
-#[tauri::command]
-fn get_data_dir() -> Result<String, String> {
-    match dirs::home_dir() {
-        Some(home) => {
-            let data_dir = home.join(".sensitive-info-tool");
-            Ok(data_dir.to_string_lossy().to_string())
-        },
-        None => Err("无法获取用户家目录".to_string()),
-    }
-}
+// 自动锁定检查间隔
+const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(15);
 
-// 确保数据目录存在
-#[tauri::command]
-fn ensure_data_dir() -> Result<(), String> {
-    match dirs::home_dir() {
-        Some(home) => {
-            let data_dir = home.join(".sensitive-info-tool");
-            if !data_dir.exists() {
-                fs::create_dir_all(&data_dir)
-                    .map_err(|e| format!("创建数据目录失败: {}", e))?;
-            }
-            Ok(())
-        },
-        None => Err("无法获取用户家目录".to_string()),
-    }
-}
-
-// 写入数据文件
-#[tauri::command]
-fn write_data_file(file_path: String, data: String) -> Result<(), String> {
-    // 确保父目录存在
-    if let Some(parent) = PathBuf::from(&file_path).parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("创建目录失败: {}", e))?;
-    }
-    
-    fs::write(&file_path, data)
-        .map_err(|e| format!("写入文件失败: {}", e))?;
-    
-    Ok(())
-}
-
-// 读取数据文件
-#[tauri::command]
-fn read_data_file(file_path: String) -> Result<String, String> {
-    fs::read_to_string(&file_path)
-        .map_err(|e| format!("读取文件失败: {}", e))
-}
+fn main() {
+    let settings = load_settings();
+    let log_state = init_logging(&settings.log_level);
 
-// 删除数据文件
-#[tauri::command]
-fn delete_data_file(file_path: String) -> Result<(), String> {
-    fs::remove_file(&file_path)
-        .map_err(|e| format!("删除文件失败: {}", e))?;
-    Ok(())
-}
+    tauri::Builder::default()
+        .manage(VaultState::new(settings.idle_timeout_secs()))
+        .manage(log_state)
+        .manage(ActiveTransfers::default())
+        .manage(TransferManager::load(settings.max_concurrent_transfers))
+        .setup(|app| {
+            report_data_dir_health();
 
-// 列出数据文件
-#[tauri::command]
-fn list_data_files() -> Result<Vec<String>, String> {
-    match dirs::home_dir() {
-        Some(home) => {
-            let data_dir = home.join(".sensitive-info-tool");
-            if !data_dir.exists() {
-                return Ok(vec![]);
-            }
-            
-            let mut files = Vec::new();
-            if let Ok(entries) = fs::read_dir(&data_dir) {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        if let Some(file_name) = entry.file_name().to_str() {
-                            if file_name.ends_with(".json") {
-                                let key = file_name.trim_end_matches(".json");
-                                files.push(key.to_string());
-                            }
-                        }
+            // 空闲超时后锁定保险库，并通知前端重新显示解锁界面；
+            // 同时在保险库解锁后启动排队中的传输任务
+            let handle = app.handle();
+            tauri::async_runtime::spawn(async move {
+                let mut ticker = tokio::time::interval(AUTO_LOCK_CHECK_INTERVAL);
+                loop {
+                    ticker.tick().await;
+                    if handle.state::<VaultState>().lock_if_idle() {
+                        let _ = handle.emit_all("vault-locked", ());
                     }
+                    handle.state::<TransferManager>().schedule(&handle);
                 }
-            }
-            Ok(files)
-        },
-        None => Err("无法获取用户家目录".to_string()),
-    }
-}
-
-
-fn main() {
-    tauri::Builder::default()
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // OSS
             oss_list_objects,
@@ -116,6 +72,61 @@ fn main() {
             oss_download_file,
             oss_delete_file,
             oss_create_folder,
+            oss_list_objects_by_id,
+            oss_list_buckets_by_id,
+            oss_upload_file_by_id,
+            oss_download_file_by_id,
+            oss_delete_file_by_id,
+            oss_create_folder_by_id,
+            oss_apply_endpoint_correction,
+            oss_presign,
+            oss_presign_by_id,
+            oss_head_object,
+            oss_head_object_by_id,
+            oss_copy_object,
+            oss_move_object,
+            oss_copy_object_by_id,
+            oss_move_object_by_id,
+            oss_delete_prefix,
+            oss_delete_prefix_by_id,
+            oss_create_bucket,
+            oss_delete_bucket,
+            oss_create_bucket_by_id,
+            oss_delete_bucket_by_id,
+            oss_get_bucket_security,
+            oss_get_bucket_security_by_id,
+            oss_check_anonymous_access,
+            oss_check_anonymous_access_by_id,
+            oss_describe_bucket,
+            oss_describe_bucket_by_id,
+            oss_upload_path,
+            oss_upload_path_by_id,
+            oss_abort_upload,
+            oss_abort_upload_by_id,
+            oss_list_resumable_uploads,
+            oss_download_to_path,
+            oss_download_to_path_by_id,
+            oss_cancel_download,
+            // 传输队列
+            transfer_enqueue_upload,
+            transfer_enqueue_download,
+            transfer_list,
+            transfer_pause,
+            transfer_resume,
+            transfer_cancel,
+            transfer_clear_finished,
+            // 保险库
+            vault_status,
+            vault_unlock,
+            vault_lock,
+            vault_get,
+            vault_put,
+            export_bundle,
+            import_bundle,
+            migrate_legacy_data,
+            // 设置
+            get_settings,
+            update_settings,
             // 文件操作
             get_home_dir,
             get_data_dir,
@@ -124,6 +135,9 @@ fn main() {
             read_data_file,
             delete_data_file,
             list_data_files,
+            list_data_backups,
+            restore_data_backup,
+            check_data_dir_health,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,239 @@
+use crate::storage::{
+    backup_dir, data_dir, list_data_files, move_to_quarantine, read_data_file, resolve_key_path, secure_remove,
+};
+use crate::records::RecordKind;
+use crate::vault::{Envelope, VaultState};
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::{command, State};
+use zeroize::Zeroizing;
+
+// 旧版前端 simpleEncrypt 使用的固定密钥
+const LEGACY_XOR_KEY: &[u8] = b"sensitive-info-tool-key-2024";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MigratedKey {
+    pub key: String,
+    // xor / base64 / plain
+    pub source: String,
+    // file：数据目录中的旧文件；local_storage：只存在于前端缓存中的旧数据
+    pub origin: String,
+    pub records: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MigrationFailure {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MigrationReport {
+    pub migrated: Vec<MigratedKey>,
+    // 已是保险库格式，无需迁移
+    pub skipped: Vec<String>,
+    pub failed: Vec<MigrationFailure>,
+    // 被安全擦除的旧格式文件（含历史备份）数量
+    pub removed_files: usize,
+}
+
+// 等价于 JS 的 decodeURIComponent
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = input.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+// 按旧方案解码：base64 -> 与固定密钥逐字节异或 -> decodeURIComponent；
+// 旧版加密失败时会退化为 base64(encodeURIComponent(text))，也可能存在未加密的 JSON
+fn decode_legacy(text: &str) -> Result<(Zeroizing<String>, &'static str), String> {
+    let text = text.trim();
+    if let Ok(raw) = B64.decode(text) {
+        let raw = Zeroizing::new(raw);
+        let xored: Zeroizing<Vec<u8>> = Zeroizing::new(
+            raw.iter()
+                .enumerate()
+                .map(|(i, b)| b ^ LEGACY_XOR_KEY[i % LEGACY_XOR_KEY.len()])
+                .collect(),
+        );
+        let candidates = [(xored.as_slice(), "xor"), (raw.as_slice(), "base64")];
+        for (bytes, source) in candidates {
+            let Ok(encoded) = std::str::from_utf8(bytes) else { continue };
+            if let Some(json) = percent_decode(encoded) {
+                if serde_json::from_str::<Value>(&json).is_ok() {
+                    return Ok((Zeroizing::new(json), source));
+                }
+            }
+        }
+    }
+    if serde_json::from_str::<Value>(text).is_ok() {
+        return Ok((Zeroizing::new(text.to_string()), "plain"));
+    }
+    Err("无法按旧格式解码".to_string())
+}
+
+fn is_vault_envelope(text: &str) -> bool {
+    serde_json::from_str::<Envelope>(text).is_ok()
+}
+
+// 擦除某个键在备份目录中残留的旧格式副本
+fn remove_legacy_backups(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else { return 0 };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let legacy = fs::read_to_string(&path).map(|t| !is_vault_envelope(&t)).unwrap_or(false);
+        if legacy && secure_remove(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+fn migrate_key(state: &VaultState, key: &str, text: &str, report: &mut MigrationReport) -> Result<(), String> {
+    let (json, source) = decode_legacy(text)?;
+    let records = record_count(&json);
+
+    // 先把旧文件移出数据键位置，写入成功后再原地擦除，写入失败则放回原处
+    let quarantined = move_to_quarantine(key)?;
+    if let Err(e) = state.store_record(key, &json) {
+        let _ = fs::rename(&quarantined, resolve_key_path(&data_dir()?, key)?);
+        return Err(e);
+    }
+    secure_remove(&quarantined)?;
+    report.removed_files += 1 + remove_legacy_backups(&backup_dir(&data_dir()?, key));
+    report.migrated.push(MigratedKey { key: key.to_string(), source: source.to_string(), origin: "file".to_string(), records });
+    Ok(())
+}
+
+fn record_count(json: &str) -> usize {
+    serde_json::from_str::<Value>(json).ok().and_then(|v| v.as_array().map(|a| a.len())).unwrap_or(1)
+}
+
+// 旧版前端把同样格式的密文缓存在 localStorage 中，数据文件丢失时只能从这里恢复；
+// 保险库中已有该键时以保险库为准
+fn migrate_cached_key(state: &VaultState, key: &str, text: &str, report: &mut MigrationReport) -> Result<(), String> {
+    if state.load_record(key)?.is_some() {
+        report.skipped.push(key.to_string());
+        return Ok(());
+    }
+    let (json, source) = decode_legacy(text)?;
+    state.store_record(key, &json)?;
+    report.migrated.push(MigratedKey {
+        key: key.to_string(),
+        source: source.to_string(),
+        origin: "local_storage".to_string(),
+        records: record_count(&json),
+    });
+    Ok(())
+}
+
+// 将旧版 XOR/base64 格式的数据文件一次性迁移到保险库格式；
+// local_cache 为前端 localStorage 中残留的旧数据，按数据键传入，只处理已登记的数据键
+#[command]
+pub fn migrate_legacy_data(
+    state: State<'_, VaultState>,
+    local_cache: Option<BTreeMap<String, String>>,
+) -> Result<MigrationReport, String> {
+    // 迁移需要用会话密钥写入新格式，先确认已解锁
+    state.with_key(|_| Ok(()))?;
+
+    let mut report = MigrationReport::default();
+    for key in list_data_files()? {
+        let text = match read_data_file(key.clone()) {
+            Ok(text) => Zeroizing::new(text),
+            Err(error) => {
+                report.failed.push(MigrationFailure { key, error });
+                continue;
+            }
+        };
+        if is_vault_envelope(&text) {
+            report.skipped.push(key);
+            continue;
+        }
+        if let Err(error) = migrate_key(&state, &key, &text, &mut report) {
+            report.failed.push(MigrationFailure { key, error });
+        }
+    }
+
+    // 数据目录中已有的键（无论迁移成功与否）不再用缓存覆盖
+    let handled: Vec<String> = report
+        .migrated
+        .iter()
+        .map(|m| m.key.clone())
+        .chain(report.skipped.iter().cloned())
+        .chain(report.failed.iter().map(|f| f.key.clone()))
+        .collect();
+    for (key, text) in local_cache.unwrap_or_default() {
+        if RecordKind::for_key(&key).is_none() || handled.contains(&key) {
+            continue;
+        }
+        let text = Zeroizing::new(text);
+        if let Err(error) = migrate_cached_key(&state, &key, &text, &mut report) {
+            report.failed.push(MigrationFailure { key, error });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 复刻旧版前端 simpleEncrypt
+    fn legacy_encrypt(text: &str) -> String {
+        let encoded: String = text
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect();
+        let xored: Vec<u8> = encoded
+            .bytes()
+            .enumerate()
+            .map(|(i, b)| b ^ LEGACY_XOR_KEY[i % LEGACY_XOR_KEY.len()])
+            .collect();
+        B64.encode(xored)
+    }
+
+    #[test]
+    fn decodes_xor_format() {
+        let json = r#"[{"id":"1","name":"测试","appSecret":"s3cr3t"}]"#;
+        let (decoded, source) = decode_legacy(&legacy_encrypt(json)).unwrap();
+        assert_eq!(source, "xor");
+        assert_eq!(decoded.as_str(), json);
+    }
+
+    #[test]
+    fn decodes_base64_fallback_and_plain() {
+        let json = r#"[{"id":"1"}]"#;
+        let (decoded, source) = decode_legacy(&B64.encode("%5B%7B%22id%22%3A%221%22%7D%5D")).unwrap();
+        assert_eq!((decoded.as_str(), source), (json, "base64"));
+
+        let (decoded, source) = decode_legacy(json).unwrap();
+        assert_eq!((decoded.as_str(), source), (json, "plain"));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(decode_legacy("not legacy data").is_err());
+    }
+}
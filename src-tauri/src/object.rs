@@ -0,0 +1,857 @@
+use crate::oss::{
+    execute_on_bucket, execute_signed, list_all_objects, list_objects_page, load_oss_config, remember_correction,
+    EndpointCorrection, OssConfig, OssOutcome, OssResult, RetryPolicy, SignedRequest, DELETE_POLICY,
+};
+use crate::sigv4::{header_value, uri_encode};
+use crate::vault::VaultState;
+use crate::xml;
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE,
+    ETAG, EXPIRES, LAST_MODIFIED,
+};
+use reqwest::Method;
+use md5::Md5;
+use s3::serde_types::Part;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use tauri::{command, State, Window};
+use tracing::{debug, warn};
+
+const HEAD_POLICY: RetryPolicy = RetryPolicy::new(12, "请求超时，请检查网络或 Endpoint 配置");
+const TAGGING_POLICY: RetryPolicy = RetryPolicy::new(12, "请求超时，请检查网络或 Endpoint 配置");
+// 服务端复制大对象耗时较长
+const COPY_POLICY: RetryPolicy = RetryPolicy::new(300, "复制超时，请检查网络或 Endpoint 配置");
+const MULTIPART_POLICY: RetryPolicy = RetryPolicy::new(60, "请求超时，请检查网络或 Endpoint 配置");
+
+const COPY_PROGRESS_EVENT: &str = "oss-copy-progress";
+const GIB: u64 = 1024 * 1024 * 1024;
+// CopyObject 单次最多复制 5GB，更大的对象使用分片复制（UploadPartCopy）
+const MAX_SINGLE_COPY_SIZE: u64 = 5 * GIB;
+const COPY_PART_SIZE: u64 = GIB / 2;
+const MAX_PARTS: u64 = 10_000;
+const COPY_CONCURRENCY: usize = 4;
+
+const DELETE_BATCH_POLICY: RetryPolicy = RetryPolicy::new(60, "删除超时，请检查网络或 Endpoint 配置");
+const DELETE_PROGRESS_EVENT: &str = "oss-delete-progress";
+// DeleteObjects 单次最多 1000 个 key
+const MAX_DELETE_BATCH: usize = 1000;
+
+// 各厂商 S3 兼容接口的扩展头前缀（AWS、阿里云、腾讯云、华为云）
+const VENDOR_PREFIXES: [&str; 4] = ["x-amz-", "x-oss-", "x-cos-", "x-obs-"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OssHeadObjectParams {
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssObjectMetadata {
+    pub key: String,
+    pub size: u64,
+    pub content_type: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    // 未返回存储类型头时为 STANDARD
+    pub storage_class: String,
+    pub server_side_encryption: Option<String>,
+    pub kms_key_id: Option<String>,
+    pub version_id: Option<String>,
+    pub cache_control: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_encoding: Option<String>,
+    pub expires: Option<String>,
+    // 自定义元数据（x-amz-meta-* / x-oss-meta-* 等），键不含前缀
+    pub user_metadata: BTreeMap<String, String>,
+    pub tag_count: Option<u32>,
+    // 无权读取标签或服务端不支持时为空
+    pub tags: Option<BTreeMap<String, String>>,
+}
+
+fn header(headers: &HeaderMap, name: impl reqwest::header::AsHeaderName) -> Option<String> {
+    headers.get(name)?.to_str().ok().map(str::to_string)
+}
+
+// 按厂商前缀查找扩展头，如 storage-class 依次尝试 x-amz-storage-class、x-oss-storage-class …
+fn vendor_header(headers: &HeaderMap, suffix: &str) -> Option<String> {
+    VENDOR_PREFIXES
+        .iter()
+        .find_map(|prefix| header(headers, format!("{}{}", prefix, suffix).as_str()))
+}
+
+fn user_metadata(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let meta_prefixes: Vec<String> = VENDOR_PREFIXES.iter().map(|p| format!("{}meta-", p)).collect();
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let name = meta_prefixes.iter().find_map(|p| name.as_str().strip_prefix(p.as_str()))?;
+            Some((name.to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect()
+}
+
+fn parse_metadata(key: &str, headers: &HeaderMap) -> OssObjectMetadata {
+    OssObjectMetadata {
+        key: key.to_string(),
+        size: header(headers, CONTENT_LENGTH).and_then(|v| v.parse().ok()).unwrap_or(0),
+        content_type: header(headers, CONTENT_TYPE),
+        etag: header(headers, ETAG),
+        last_modified: header(headers, LAST_MODIFIED),
+        storage_class: vendor_header(headers, "storage-class").unwrap_or_else(|| "STANDARD".to_string()),
+        server_side_encryption: vendor_header(headers, "server-side-encryption"),
+        kms_key_id: vendor_header(headers, "server-side-encryption-aws-kms-key-id")
+            .or_else(|| vendor_header(headers, "server-side-encryption-key-id")),
+        version_id: vendor_header(headers, "version-id"),
+        cache_control: header(headers, CACHE_CONTROL),
+        content_disposition: header(headers, CONTENT_DISPOSITION),
+        content_encoding: header(headers, CONTENT_ENCODING),
+        expires: header(headers, EXPIRES),
+        user_metadata: user_metadata(headers),
+        tag_count: vendor_header(headers, "tagging-count").and_then(|v| v.parse().ok()),
+        tags: None,
+    }
+}
+
+fn parse_tagging(body: &str) -> BTreeMap<String, String> {
+    xml::blocks(body, "Tag")
+        .into_iter()
+        .filter_map(|tag| Some((xml::text(tag, "Key")?, xml::text(tag, "Value").unwrap_or_default())))
+        .collect()
+}
+
+#[command]
+pub async fn oss_head_object(cfg: OssConfig, params: OssHeadObjectParams) -> Result<OssResult<OssObjectMetadata>, String> {
+    let key = params.key.as_str();
+    let head = execute_signed(
+        &cfg,
+        HEAD_POLICY,
+        &SignedRequest { method: Method::HEAD, key: Some(key), query: &[], headers: HeaderMap::new(), body: Vec::new() },
+    )
+    .await?;
+    let mut metadata = parse_metadata(key, &head.value.headers);
+
+    let mut cfg = cfg.clone();
+    if let Some(c) = &head.correction {
+        c.apply(&mut cfg);
+    }
+    // 返回了标签数量且为 0 时不再请求标签
+    if metadata.tag_count != Some(0) {
+        let tagging = execute_signed(
+            &cfg,
+            TAGGING_POLICY,
+            &SignedRequest { method: Method::GET, key: Some(key), query: &[("tagging", "")], headers: HeaderMap::new(), body: Vec::new() },
+        )
+        .await;
+        match tagging {
+            Ok(outcome) => metadata.tags = Some(parse_tagging(&outcome.value.body)),
+            Err(e) => debug!("object tagging unavailable: {}", e),
+        }
+    }
+
+    Ok(OssResult {
+        success: true,
+        data: Some(metadata),
+        message: None,
+        error: None,
+        correction: head.correction,
+    })
+}
+
+#[command]
+pub async fn oss_head_object_by_id(vault: State<'_, VaultState>, config_id: String, params: OssHeadObjectParams) -> Result<OssResult<OssObjectMetadata>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_head_object(cfg, params).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OssCopyParams {
+    // 以 / 结尾时按前缀处理其下全部对象
+    pub source_key: String,
+    pub dest_key: String,
+    // 只返回将要复制的对象与冲突，不实际执行
+    pub dry_run: Option<bool>,
+    // 目标已存在时是否覆盖，默认不覆盖
+    pub overwrite: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssCopyItem {
+    pub source_key: String,
+    pub dest_key: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssCopyFailure {
+    pub key: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssCopySummary {
+    pub dry_run: bool,
+    pub items: Vec<OssCopyItem>,
+    // 目标位置已存在的 key
+    pub conflicts: Vec<String>,
+    pub copied: u32,
+    pub failed: Vec<OssCopyFailure>,
+}
+
+// oss-copy-progress 事件内容，每完成一个对象发送一次
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CopyProgress {
+    pub source_key: String,
+    pub dest_key: String,
+    pub done: u32,
+    pub total: u32,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+fn copy_source_headers(cfg: &OssConfig, source_key: &str) -> Result<HeaderMap, String> {
+    let bucket = cfg.bucket.as_deref().ok_or_else(|| "Missing bucket name".to_string())?;
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        HeaderName::from_static("x-amz-copy-source"),
+        header_value(&format!("/{}/{}", bucket, uri_encode(source_key, true)))?,
+    );
+    Ok(headers)
+}
+
+// CopyObject / UploadPartCopy 可能在返回 200 后才报告失败，错误放在响应体里
+fn copy_result_etag(body: &str) -> Result<String, String> {
+    if let Some(error) = xml::blocks(body, "Error").first() {
+        let code = xml::text(error, "Code").unwrap_or_default();
+        let message = xml::text(error, "Message").unwrap_or_default();
+        return Err(format!("复制失败: {} {}", code, message));
+    }
+    xml::text(body, "ETag").ok_or_else(|| "复制失败: 响应中缺少 ETag".to_string())
+}
+
+// 前缀复制的目标补齐结尾的 /；单个对象复制到以 / 结尾的目录时沿用源文件名
+fn copy_destination(source: &str, dest_key: &str) -> String {
+    match (source.ends_with('/'), dest_key.ends_with('/')) {
+        (true, false) => format!("{}/", dest_key),
+        (false, true) => format!("{}{}", dest_key, source.rsplit('/').next().unwrap_or(source)),
+        _ => dest_key.to_string(),
+    }
+}
+
+// 前缀复制时把源前缀替换为目标前缀
+fn plan_copy(source_prefix: &str, dest_prefix: &str, objects: Vec<(String, u64)>) -> Vec<OssCopyItem> {
+    objects
+        .into_iter()
+        .filter_map(|(key, size)| {
+            let rest = key.strip_prefix(source_prefix)?;
+            Some(OssCopyItem { dest_key: format!("{}{}", dest_prefix, rest), source_key: key, size })
+        })
+        .collect()
+}
+
+fn copy_part_ranges(size: u64) -> Vec<(u32, u64, u64)> {
+    let part_size = COPY_PART_SIZE.max(size.div_ceil(MAX_PARTS));
+    (0..size.div_ceil(part_size))
+        .map(|i| {
+            let start = i * part_size;
+            ((i + 1) as u32, start, (start + part_size).min(size) - 1)
+        })
+        .collect()
+}
+
+// 分片复制不会带上源对象的元数据，发起分片上传时按 HEAD 结果补上
+fn initiate_copy_headers(source: &HeaderMap) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    let content_type = header(source, CONTENT_TYPE).unwrap_or_else(|| "application/octet-stream".to_string());
+    headers.insert(CONTENT_TYPE, header_value(&content_type)?);
+    for name in [CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_ENCODING, EXPIRES] {
+        if let Some(value) = source.get(&name) {
+            headers.insert(name, value.clone());
+        }
+    }
+    for (name, value) in user_metadata(source) {
+        let name = HeaderName::from_bytes(format!("x-amz-meta-{}", name).as_bytes())
+            .map_err(|_| format!("元数据名称不合法: {}", name))?;
+        headers.insert(name, header_value(&value)?);
+    }
+    Ok(headers)
+}
+
+async fn multipart_copy(cfg: &OssConfig, source_key: &str, dest_key: &str, size: u64) -> Result<(), String> {
+    let head = execute_signed(
+        cfg,
+        HEAD_POLICY,
+        &SignedRequest { method: Method::HEAD, key: Some(source_key), query: &[], headers: HeaderMap::new(), body: Vec::new() },
+    )
+    .await?;
+    let initiate = execute_signed(
+        cfg,
+        MULTIPART_POLICY,
+        &SignedRequest {
+            method: Method::POST,
+            key: Some(dest_key),
+            query: &[("uploads", "")],
+            headers: initiate_copy_headers(&head.value.headers)?,
+            body: Vec::new(),
+        },
+    )
+    .await?;
+    let upload_id = xml::text(&initiate.value.body, "UploadId").ok_or_else(|| "发起分片复制失败: 响应中缺少 UploadId".to_string())?;
+
+    let upload_id_ref = upload_id.as_str();
+    let parts: Result<Vec<Part>, String> = stream::iter(copy_part_ranges(size))
+        .map(|(part_number, start, end)| async move {
+            let mut headers = copy_source_headers(cfg, source_key)?;
+            headers.insert(
+                HeaderName::from_static("x-amz-copy-source-range"),
+                header_value(&format!("bytes={}-{}", start, end))?,
+            );
+            let part_number_text = part_number.to_string();
+            let query = [("partNumber", part_number_text.as_str()), ("uploadId", upload_id_ref)];
+            let outcome = execute_signed(
+                cfg,
+                COPY_POLICY,
+                &SignedRequest { method: Method::PUT, key: Some(dest_key), query: &query, headers, body: Vec::new() },
+            )
+            .await?;
+            Ok(Part { part_number, etag: copy_result_etag(&outcome.value.body)? })
+        })
+        .buffer_unordered(COPY_CONCURRENCY)
+        .try_collect()
+        .await;
+
+    let result = match parts {
+        Ok(mut parts) => {
+            parts.sort_by_key(|p| p.part_number);
+            let parts = &parts;
+            execute_on_bucket(cfg, MULTIPART_POLICY, |bucket| async move {
+                bucket.complete_multipart_upload(dest_key, upload_id_ref, parts.clone()).await
+            })
+            .await
+            .map(|_| ())
+        }
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        let _ = execute_on_bucket(cfg, MULTIPART_POLICY, |bucket| async move { bucket.abort_upload(dest_key, upload_id_ref).await }).await;
+    }
+    result
+}
+
+async fn copy_one(cfg: &OssConfig, item: &OssCopyItem) -> Result<(), String> {
+    if item.size > MAX_SINGLE_COPY_SIZE {
+        return multipart_copy(cfg, &item.source_key, &item.dest_key, item.size).await;
+    }
+    let outcome = execute_signed(
+        cfg,
+        COPY_POLICY,
+        &SignedRequest {
+            method: Method::PUT,
+            key: Some(&item.dest_key),
+            query: &[],
+            headers: copy_source_headers(cfg, &item.source_key)?,
+            body: Vec::new(),
+        },
+    )
+    .await?;
+    copy_result_etag(&outcome.value.body).map(|_| ())
+}
+
+async fn copy_objects(
+    window: &Window,
+    cfg: &OssConfig,
+    params: &OssCopyParams,
+    delete_source: bool,
+) -> Result<OssResult<OssCopySummary>, String> {
+    let source = params.source_key.as_str();
+    if source.is_empty() || params.dest_key.is_empty() {
+        return Err("源路径和目标路径不能为空".to_string());
+    }
+    let is_prefix = source.ends_with('/');
+    let dest = copy_destination(source, &params.dest_key);
+    if dest == source || (is_prefix && dest.starts_with(source)) {
+        return Err("目标路径不能是源路径本身或其子目录".to_string());
+    }
+
+    let mut cfg = cfg.clone();
+    let mut correction: Option<EndpointCorrection> = None;
+    let mut remember = |c: Option<EndpointCorrection>, cfg: &mut OssConfig| {
+        if let Some(c) = c {
+            c.apply(cfg);
+            correction = Some(c);
+        }
+    };
+
+    // 源对象与目标位置已有的对象
+    let (items, existing): (Vec<OssCopyItem>, HashSet<String>) = if is_prefix {
+        let outcome = list_all_objects(&cfg, source).await?;
+        remember(outcome.correction, &mut cfg);
+        let items = plan_copy(source, &dest, outcome.value.into_iter().map(|o| (o.key, o.size)).collect());
+        let outcome = list_all_objects(&cfg, &dest).await?;
+        remember(outcome.correction, &mut cfg);
+        (items, outcome.value.into_iter().map(|o| o.key).collect())
+    } else {
+        // 按 key 前缀列举，第一个结果与 key 相同即表示对象存在
+        let outcome = list_objects_page(&cfg, source, "", None, None, 1).await?;
+        remember(outcome.correction, &mut cfg);
+        let size = outcome
+            .value
+            .items
+            .into_iter()
+            .find(|o| o.key == source)
+            .map(|o| o.size)
+            .ok_or_else(|| format!("对象不存在: {}", source))?;
+        let outcome = list_objects_page(&cfg, &dest, "", None, None, 1).await?;
+        remember(outcome.correction, &mut cfg);
+        let existing = outcome.value.items.into_iter().map(|o| o.key).filter(|k| *k == dest).collect();
+        (vec![OssCopyItem { source_key: source.to_string(), dest_key: dest.clone(), size }], existing)
+    };
+    if items.is_empty() {
+        return Err(format!("前缀下没有对象: {}", source));
+    }
+    let conflicts: Vec<String> = items.iter().filter(|i| existing.contains(&i.dest_key)).map(|i| i.dest_key.clone()).collect();
+
+    let dry_run = params.dry_run.unwrap_or(false);
+    if !dry_run && !conflicts.is_empty() && !params.overwrite.unwrap_or(false) {
+        return Err(format!("目标位置已存在 {} 个同名对象，如需覆盖请开启 overwrite", conflicts.len()));
+    }
+    let mut summary = OssCopySummary { dry_run, items, conflicts, copied: 0, failed: Vec::new() };
+    if dry_run {
+        return Ok(OssResult {
+            success: true,
+            message: Some(format!("将{}{} 个对象", if delete_source { "移动" } else { "复制" }, summary.items.len())),
+            data: Some(summary),
+            error: None,
+            correction,
+        });
+    }
+
+    let total = summary.items.len() as u32;
+    let bytes_total: u64 = summary.items.iter().map(|i| i.size).sum();
+    let mut bytes_done = 0;
+    let cfg = &cfg;
+    let mut results = stream::iter(summary.items.clone())
+        .map(|item| async move {
+            let result = async {
+                copy_one(cfg, &item).await?;
+                if delete_source {
+                    let key = item.source_key.as_str();
+                    execute_on_bucket(cfg, DELETE_POLICY, |bucket| async move { bucket.delete_object(key).await })
+                        .await
+                        .map_err(|e| format!("已复制，但删除源对象失败: {}", e))?;
+                }
+                Ok::<_, String>(())
+            }
+            .await;
+            (item, result)
+        })
+        .buffer_unordered(COPY_CONCURRENCY);
+    while let Some((item, result)) = results.next().await {
+        match result {
+            Ok(()) => summary.copied += 1,
+            Err(error) => {
+                warn!("copy failed: {}", error);
+                summary.failed.push(OssCopyFailure { key: item.source_key.clone(), error });
+            }
+        }
+        bytes_done += item.size;
+        let _ = window.emit(
+            COPY_PROGRESS_EVENT,
+            CopyProgress {
+                source_key: item.source_key,
+                dest_key: item.dest_key,
+                done: summary.copied + summary.failed.len() as u32,
+                total,
+                bytes_done,
+                bytes_total,
+            },
+        );
+    }
+
+    let action = if delete_source { "移动" } else { "复制" };
+    let (message, error) = if summary.failed.is_empty() {
+        (Some(format!("已{} {} 个对象", action, summary.copied)), None)
+    } else {
+        (None, Some(format!("{} 个对象{}失败", summary.failed.len(), action)))
+    };
+    Ok(OssResult {
+        success: summary.failed.is_empty(),
+        data: Some(summary),
+        message,
+        error,
+        correction,
+    })
+}
+
+// 服务端复制对象；source_key 以 / 结尾时复制整个前缀
+#[command]
+pub async fn oss_copy_object(window: Window, cfg: OssConfig, params: OssCopyParams) -> Result<OssResult<OssCopySummary>, String> {
+    copy_objects(&window, &cfg, &params, false).await
+}
+
+// 移动或重命名：复制成功后删除源对象，复制失败的对象保留在原处
+#[command]
+pub async fn oss_move_object(window: Window, cfg: OssConfig, params: OssCopyParams) -> Result<OssResult<OssCopySummary>, String> {
+    copy_objects(&window, &cfg, &params, true).await
+}
+
+#[command]
+pub async fn oss_copy_object_by_id(
+    window: Window,
+    vault: State<'_, VaultState>,
+    config_id: String,
+    params: OssCopyParams,
+) -> Result<OssResult<OssCopySummary>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = copy_objects(&window, &cfg, &params, false).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[command]
+pub async fn oss_move_object_by_id(
+    window: Window,
+    vault: State<'_, VaultState>,
+    config_id: String,
+    params: OssCopyParams,
+) -> Result<OssResult<OssCopySummary>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = copy_objects(&window, &cfg, &params, true).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OssDeletePrefixParams {
+    pub prefix: String,
+    // 只统计将要删除的对象，并返回确认令牌
+    pub dry_run: Option<bool>,
+    // 预览时返回的令牌；前缀下的对象有变化时令牌失效，需要重新预览
+    pub confirm_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssDeleteFailure {
+    pub key: String,
+    pub code: Option<String>,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssDeletePrefixSummary {
+    pub dry_run: bool,
+    pub prefix: String,
+    pub count: u32,
+    pub total_size: u64,
+    pub confirm_token: Option<String>,
+    pub deleted: u32,
+    pub failed: Vec<OssDeleteFailure>,
+}
+
+// oss-delete-progress 事件内容，每完成一批发送一次
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteProgress {
+    pub prefix: String,
+    pub done: u32,
+    pub total: u32,
+}
+
+// 令牌由桶、前缀和对象清单计算，预览与执行之间对象有增删或大小变化都会对不上
+fn delete_confirm_token(bucket: &str, prefix: &str, objects: &[(String, u64)]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bucket.as_bytes());
+    hasher.update([0]);
+    hasher.update(prefix.as_bytes());
+    for (key, size) in objects {
+        hasher.update([0]);
+        hasher.update(key.as_bytes());
+        hasher.update(size.to_le_bytes());
+    }
+    hex::encode(&hasher.finalize()[..8])
+}
+
+// Quiet 模式下响应只包含删除失败的对象
+fn delete_request_body(keys: &[String]) -> String {
+    let objects: String = keys.iter().map(|key| format!("<Object><Key>{}</Key></Object>", xml::escape(key))).collect();
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?><Delete><Quiet>true</Quiet>{}</Delete>"#, objects)
+}
+
+fn parse_delete_errors(body: &str) -> Vec<OssDeleteFailure> {
+    xml::blocks(body, "Error")
+        .into_iter()
+        .filter_map(|block| {
+            Some(OssDeleteFailure {
+                key: xml::text(block, "Key")?,
+                code: xml::text(block, "Code"),
+                error: xml::text(block, "Message").unwrap_or_else(|| "删除失败".to_string()),
+            })
+        })
+        .collect()
+}
+
+// 只保留本批次中 key 的错误，每个 key 至多一条；服务端返回重复或多余的错误时不影响计数
+fn batch_failures(batch: &[String], errors: Vec<OssDeleteFailure>) -> Vec<OssDeleteFailure> {
+    let mut seen = HashSet::new();
+    errors
+        .into_iter()
+        .filter(|f| batch.contains(&f.key) && seen.insert(f.key.clone()))
+        .collect()
+}
+
+async fn delete_batch(cfg: &OssConfig, keys: &[String]) -> Result<OssOutcome<Vec<OssDeleteFailure>>, String> {
+    let body = delete_request_body(keys).into_bytes();
+    let mut headers = HeaderMap::new();
+    headers.insert(HeaderName::from_static("content-md5"), header_value(&B64.encode(Md5::digest(&body)))?);
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/xml"));
+    let outcome = execute_signed(
+        cfg,
+        DELETE_BATCH_POLICY,
+        &SignedRequest { method: Method::POST, key: None, query: &[("delete", "")], headers, body },
+    )
+    .await?;
+    Ok(OssOutcome { value: parse_delete_errors(&outcome.value.body), correction: outcome.correction })
+}
+
+// 按批删除给定的 key，单批请求失败时该批全部记为失败并继续下一批；
+// 返回成功删除的数量与失败明细
+pub(crate) async fn delete_keys(
+    window: &Window,
+    cfg: &OssConfig,
+    prefix: &str,
+    keys: &[String],
+) -> OssOutcome<(u32, Vec<OssDeleteFailure>)> {
+    let mut cfg = cfg.clone();
+    let mut correction: Option<EndpointCorrection> = None;
+    let mut deleted = 0;
+    let mut failed = Vec::new();
+    let mut done = 0;
+    for batch in keys.chunks(MAX_DELETE_BATCH) {
+        match delete_batch(&cfg, batch).await {
+            Ok(outcome) => {
+                if let Some(c) = outcome.correction {
+                    c.apply(&mut cfg);
+                    correction = Some(c);
+                }
+                let batch_failed = batch_failures(batch, outcome.value);
+                deleted += batch.len().saturating_sub(batch_failed.len()) as u32;
+                failed.extend(batch_failed);
+            }
+            Err(error) => {
+                warn!("delete batch failed: {}", error);
+                failed.extend(batch.iter().map(|key| OssDeleteFailure { key: key.clone(), code: None, error: error.clone() }));
+            }
+        }
+        done += batch.len() as u32;
+        let _ = window.emit(
+            DELETE_PROGRESS_EVENT,
+            DeleteProgress { prefix: prefix.to_string(), done, total: keys.len() as u32 },
+        );
+    }
+    OssOutcome { value: (deleted, failed), correction }
+}
+
+async fn delete_prefix(window: &Window, cfg: &OssConfig, params: &OssDeletePrefixParams) -> Result<OssResult<OssDeletePrefixSummary>, String> {
+    let trimmed = params.prefix.trim_start_matches('/');
+    if trimmed.is_empty() {
+        return Err("前缀不能为空，不支持清空整个存储桶".to_string());
+    }
+    let prefix = if trimmed.ends_with('/') { trimmed.to_string() } else { format!("{}/", trimmed) };
+    let bucket = cfg.bucket.as_deref().ok_or_else(|| "Missing bucket name".to_string())?;
+
+    let mut cfg = cfg.clone();
+    let mut correction: Option<EndpointCorrection> = None;
+    let outcome = list_all_objects(&cfg, &prefix).await?;
+    if let Some(c) = outcome.correction {
+        c.apply(&mut cfg);
+        correction = Some(c);
+    }
+    let objects: Vec<(String, u64)> = outcome.value.into_iter().map(|o| (o.key, o.size)).collect();
+    if objects.is_empty() {
+        return Err(format!("前缀下没有对象: {}", prefix));
+    }
+    let token = delete_confirm_token(bucket, &prefix, &objects);
+
+    let dry_run = params.dry_run.unwrap_or(false);
+    let mut summary = OssDeletePrefixSummary {
+        dry_run,
+        prefix: prefix.clone(),
+        count: objects.len() as u32,
+        total_size: objects.iter().map(|(_, size)| size).sum(),
+        confirm_token: None,
+        deleted: 0,
+        failed: Vec::new(),
+    };
+    if dry_run {
+        summary.confirm_token = Some(token);
+        return Ok(OssResult {
+            success: true,
+            message: Some(format!("将删除 {} 个对象", summary.count)),
+            data: Some(summary),
+            error: None,
+            correction,
+        });
+    }
+    match params.confirm_token.as_deref() {
+        None => return Err("删除前缀需要先预览并提供确认令牌".to_string()),
+        Some(t) if t != token => return Err("前缀下的对象已发生变化，请重新预览后再删除".to_string()),
+        Some(_) => {}
+    }
+
+    let keys: Vec<String> = objects.into_iter().map(|(key, _)| key).collect();
+    let outcome = delete_keys(window, &cfg, &prefix, &keys).await;
+    (summary.deleted, summary.failed) = outcome.value;
+    correction = outcome.correction.or(correction);
+
+    let (message, error) = if summary.failed.is_empty() {
+        (Some(format!("已删除 {} 个对象", summary.deleted)), None)
+    } else {
+        (None, Some(format!("{} 个对象删除失败", summary.failed.len())))
+    };
+    Ok(OssResult {
+        success: summary.failed.is_empty(),
+        data: Some(summary),
+        message,
+        error,
+        correction,
+    })
+}
+
+// 删除前缀下的全部对象：先 dry_run 取得数量、大小与确认令牌，再带令牌执行
+#[command]
+pub async fn oss_delete_prefix(
+    window: Window,
+    cfg: OssConfig,
+    params: OssDeletePrefixParams,
+) -> Result<OssResult<OssDeletePrefixSummary>, String> {
+    delete_prefix(&window, &cfg, &params).await
+}
+
+#[command]
+pub async fn oss_delete_prefix_by_id(
+    window: Window,
+    vault: State<'_, VaultState>,
+    config_id: String,
+    params: OssDeletePrefixParams,
+) -> Result<OssResult<OssDeletePrefixSummary>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = delete_prefix(&window, &cfg, &params).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn parses_vendor_headers() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("content-length", "42"),
+            ("content-type", "text/plain"),
+            ("etag", "\"abc\""),
+            ("x-oss-storage-class", "Archive"),
+            ("x-oss-server-side-encryption", "KMS"),
+            ("x-oss-server-side-encryption-key-id", "key-1"),
+            ("x-oss-meta-owner", "ops"),
+            ("x-amz-meta-source", "import"),
+            ("x-amz-tagging-count", "2"),
+        ] {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+        let meta = parse_metadata("a.txt", &headers);
+        assert_eq!(meta.size, 42);
+        assert_eq!(meta.storage_class, "Archive");
+        assert_eq!(meta.server_side_encryption.as_deref(), Some("KMS"));
+        assert_eq!(meta.kms_key_id.as_deref(), Some("key-1"));
+        assert_eq!(meta.user_metadata.len(), 2);
+        assert_eq!(meta.user_metadata["owner"], "ops");
+        assert_eq!(meta.tag_count, Some(2));
+
+        let meta = parse_metadata("b", &HeaderMap::new());
+        assert_eq!(meta.storage_class, "STANDARD");
+    }
+
+    #[test]
+    fn plans_prefix_copy() {
+        let plan = plan_copy("a/", "b/c/", vec![("a/".to_string(), 0), ("a/x/y.txt".to_string(), 3)]);
+        assert_eq!(plan[0].dest_key, "b/c/");
+        assert_eq!(plan[1].dest_key, "b/c/x/y.txt");
+        assert_eq!(plan[1].size, 3);
+    }
+
+    #[test]
+    fn resolves_copy_destination() {
+        assert_eq!(copy_destination("a/b.txt", "c/"), "c/b.txt");
+        assert_eq!(copy_destination("b.txt", "c/d/"), "c/d/b.txt");
+        assert_eq!(copy_destination("a/b.txt", "c/e.txt"), "c/e.txt");
+        assert_eq!(copy_destination("a/", "c"), "c/");
+        assert_eq!(copy_destination("a/", "c/"), "c/");
+    }
+
+    #[test]
+    fn carries_metadata_into_multipart_copy() {
+        let mut source = HeaderMap::new();
+        source.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        source.insert(CONTENT_LENGTH, HeaderValue::from_static("42"));
+        source.insert("x-oss-meta-owner", HeaderValue::from_static("ops"));
+        let headers = initiate_copy_headers(&source).unwrap();
+        assert_eq!(header(&headers, CONTENT_TYPE).as_deref(), Some("application/octet-stream"));
+        assert_eq!(header(&headers, CACHE_CONTROL).as_deref(), Some("no-cache"));
+        assert_eq!(header(&headers, "x-amz-meta-owner").as_deref(), Some("ops"));
+        assert!(headers.get(CONTENT_LENGTH).is_none());
+    }
+
+    #[test]
+    fn splits_large_copies_into_ranges() {
+        let size = 5 * GIB + 1;
+        let ranges = copy_part_ranges(size);
+        assert_eq!(ranges.len(), 11);
+        assert_eq!(ranges[0], (1, 0, COPY_PART_SIZE - 1));
+        assert_eq!(ranges.last().unwrap().2, size - 1);
+        assert!(copy_part_ranges(MAX_PARTS * 10 * GIB).len() as u64 <= MAX_PARTS);
+    }
+
+    #[test]
+    fn detects_copy_errors_in_body() {
+        assert_eq!(copy_result_etag("<CopyObjectResult><ETag>\"e\"</ETag></CopyObjectResult>").unwrap(), "\"e\"");
+        assert!(copy_result_etag("<Error><Code>InternalError</Code><Message>m</Message></Error>").is_err());
+    }
+
+    #[test]
+    fn parses_tag_set() {
+        let body = "<Tagging><TagSet><Tag><Key>env</Key><Value>prod</Value></Tag></TagSet></Tagging>";
+        assert_eq!(parse_tagging(body).get("env").map(String::as_str), Some("prod"));
+    }
+
+    #[test]
+    fn builds_delete_request_and_parses_errors() {
+        let body = delete_request_body(&["a&b.txt".to_string(), "dir/".to_string()]);
+        assert!(body.contains("<Quiet>true</Quiet><Object><Key>a&amp;b.txt</Key></Object><Object><Key>dir/</Key></Object>"));
+
+        let response = r#"<DeleteResult><Error><Key>a&amp;b.txt</Key><Code>AccessDenied</Code><Message>Access Denied</Message></Error></DeleteResult>"#;
+        let failed = parse_delete_errors(response);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].key, "a&b.txt");
+        assert_eq!(failed[0].code.as_deref(), Some("AccessDenied"));
+        assert!(parse_delete_errors("<DeleteResult/>").is_empty());
+
+        let response = r#"<DeleteResult><Error><Key>a</Key></Error><Error><Key>a</Key></Error><Error><Key>x</Key></Error></DeleteResult>"#;
+        let failed = batch_failures(&["a".to_string()], parse_delete_errors(response));
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].key, "a");
+    }
+
+    #[test]
+    fn confirm_token_tracks_listing() {
+        let objects = vec![("p/a".to_string(), 1), ("p/b".to_string(), 2)];
+        let token = delete_confirm_token("bucket", "p/", &objects);
+        assert_eq!(token, delete_confirm_token("bucket", "p/", &objects));
+        assert_ne!(token, delete_confirm_token("bucket", "p/", &objects[..1]));
+        assert_ne!(token, delete_confirm_token("other", "p/", &objects));
+    }
+}
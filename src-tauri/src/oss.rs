@@ -1,14 +1,32 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use s3::bucket::Bucket;
 use s3::creds::Credentials;
+use s3::error::S3Error;
 use s3::region::Region;
-use tauri::command;
+use tauri::{command, State};
 use std::str::FromStr;
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
+use rand::Rng;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use crate::records::{AuthType, CloudOssConfig, CLOUD_OSS_CONFIGS_KEY};
+use crate::settings::load_settings;
+use crate::vault::VaultState;
+use crate::logging::{redact, redact_error};
+use crate::xml;
+use crate::sigv4::{payload_hash, sign, uri_encode, SigningKeys};
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use url::Url;
+use tracing::{debug, warn};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+// 凭证在 drop 时清零，避免解密后的 AK/SK 残留在内存中
+#[derive(Debug, Serialize, Deserialize, Clone, Zeroize, ZeroizeOnDrop)]
 pub struct OssConfig {
     pub provider: String,
     pub access_key: String,
@@ -25,14 +43,38 @@ pub struct OssListParams {
     pub prefix: Option<String>,
     pub delimiter: Option<String>,
     pub max_keys: Option<u32>,
+    // 上一页返回的 next_token，为空时从头列举
+    pub continuation_token: Option<String>,
+    // 上一页返回的 next_start_after，服务端不支持 continuation token 时使用
+    pub start_after: Option<String>,
 }
 
+// S3 兼容接口单次最多返回 1000 个 key
+const MAX_KEYS_PER_PAGE: u32 = 1000;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OssFileItem {
     pub key: String,
     pub size: u64,
     pub last_modified: Option<String>,
     pub is_directory: bool,
+    pub etag: Option<String>,
+    // STANDARD / STANDARD_IA / GLACIER / Archive / ColdArchive 等，取值因厂商而异
+    pub storage_class: Option<String>,
+    pub owner_id: Option<String>,
+    pub owner_display_name: Option<String>,
+    // 上传时指定的校验算法（CRC32 / CRC32C / SHA1 / SHA256），多数厂商不返回
+    pub checksum_algorithm: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssListPage {
+    pub items: Vec<OssFileItem>,
+    // 还有更多结果时，作为下一次请求的 continuation_token
+    pub next_token: Option<String>,
+    // 服务端未返回 continuation token 时，作为下一次请求的 start_after
+    pub next_start_after: Option<String>,
+    pub is_truncated: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,6 +83,9 @@ pub struct OssResult<T> {
     pub data: Option<T>,
     pub message: Option<String>,
     pub error: Option<String>,
+    // 请求被 PermanentRedirect 修正时返回实际的 region/endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correction: Option<EndpointCorrection>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,78 +112,167 @@ pub struct OssCreateFolderParams {
     pub path: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OssPresignParams {
+    pub key: String,
+    // GET 或 PUT，默认 GET
+    pub method: Option<String>,
+    // 有效期（秒），默认 1 小时，最长 7 天
+    pub expires_secs: Option<u32>,
+    // 下载时覆盖的响应头，仅 GET 可用
+    pub response_content_type: Option<String>,
+    pub response_content_disposition: Option<String>,
+    pub response_cache_control: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssPresignedUrl {
+    pub url: String,
+    pub method: String,
+    // 链接实际失效时间；使用 STS 临时凭证时不晚于凭证过期时间
+    pub expires_at: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OssBucketSummary {
     pub name: String,
     pub creation_date: Option<String>,
 }
 
-fn resolve_region_and_endpoint(cfg: &OssConfig) -> Result<(Region, Option<String>), String> {
+// 返回签名区域、endpoint，以及创建存储桶时 CreateBucketConfiguration 中的 LocationConstraint
+// （为空表示不发送，由 endpoint 决定存储桶所在区域）
+pub(crate) fn resolve_region_and_endpoint(cfg: &OssConfig) -> Result<(Region, Option<String>, Option<String>), String> {
     // 如果用户提供了自定义endpoint，优先使用
     if let Some(ep) = &cfg.endpoint {
         let region = Region::Custom {
             region: cfg.region.clone().unwrap_or_else(|| "custom".to_string()),
             endpoint: ep.clone(),
         };
-        return Ok((region, Some(ep.clone())));
+        let location = match cfg.provider.as_str() {
+            // 腾讯云与 MinIO 不识别 LocationConstraint
+            "tencent" | "minio" => None,
+            "qiniu" => cfg.region.as_deref().map(qiniu_s3_region),
+            _ => cfg.region.clone().filter(|r| r != "us-east-1"),
+        };
+        return Ok((region, Some(ep.clone()), location));
     }
 
     // 根据云厂商自动生成endpoint
-    let (region, endpoint) = match cfg.provider.as_str() {
+    let (region, endpoint, location) = match cfg.provider.as_str() {
         "aws" => {
             let region_name = cfg.region.clone().unwrap_or_else(|| "us-east-1".to_string());
-            (Region::from_str(&region_name).map_err(|e| e.to_string())?, None)
+            // us-east-1 是默认区域，显式指定反而会被拒绝
+            let location = Some(region_name.clone()).filter(|r| r != "us-east-1");
+            (Region::from_str(&region_name).map_err(redact_error)?, None, location)
         }
         "aliyun" => {
             let region_name = cfg.region.clone().unwrap_or_else(|| "oss-cn-hangzhou".to_string());
             let endpoint = format!("https://{}.aliyuncs.com", region_name);
-            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint))
+            (Region::Custom { region: region_name.clone(), endpoint: endpoint.clone() }, Some(endpoint), Some(region_name))
         }
         "tencent" => {
+            // COS 按请求的 endpoint 确定区域
             let region_name = cfg.region.clone().unwrap_or_else(|| "ap-beijing".to_string());
             let endpoint = format!("https://cos.{}.myqcloud.com", region_name);
-            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint))
+            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint), None)
         }
         "huawei" => {
+            // OBS 在默认区域以外创建存储桶必须指定 Location
             let region_name = cfg.region.clone().unwrap_or_else(|| "cn-north-1".to_string());
             let endpoint = format!("https://obs.{}.myhuaweicloud.com", region_name);
-            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint))
+            (Region::Custom { region: region_name.clone(), endpoint: endpoint.clone() }, Some(endpoint), Some(region_name))
         }
         "qiniu" => {
+            // 七牛的区域 ID（z0 等）与 S3 区域名（cn-east-1 等）不同，LocationConstraint 使用后者
             let region_name = cfg.region.clone().unwrap_or_else(|| "z0".to_string());
-            let endpoint = match region_name.as_str() {
-                "z0" => "https://s3-cn-east-1.qiniucs.com".to_string(),
-                "z1" => "https://s3-cn-north-1.qiniucs.com".to_string(),
-                "z2" => "https://s3-cn-south-1.qiniucs.com".to_string(),
-                "na0" => "https://s3-us-north-1.qiniucs.com".to_string(),
-                "as0" => "https://s3-ap-southeast-1.qiniucs.com".to_string(),
-                _ => format!("https://s3-{}.qiniucs.com", region_name),
-            };
-            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint))
+            let s3_region = qiniu_s3_region(&region_name);
+            let endpoint = format!("https://s3-{}.qiniucs.com", s3_region);
+            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint), Some(s3_region))
         }
         "jdcloud" => {
             let region_name = cfg.region.clone().unwrap_or_else(|| "cn-north-1".to_string());
             let endpoint = format!("https://s3.{}.jdcloud-oss.com", region_name);
-            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint))
+            (Region::Custom { region: region_name.clone(), endpoint: endpoint.clone() }, Some(endpoint), Some(region_name))
         }
         "ksyun" => {
             let region_name = cfg.region.clone().unwrap_or_else(|| "cn-beijing-6".to_string());
             let endpoint = format!("https://ks3-{}.ksyuncs.com", region_name);
-            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint))
+            (Region::Custom { region: region_name.clone(), endpoint: endpoint.clone() }, Some(endpoint), Some(region_name))
         }
         "qingcloud" => {
             let region_name = cfg.region.clone().unwrap_or_else(|| "pek3a".to_string());
             let endpoint = format!("https://s3.{}.qingstor.com", region_name);
-            (Region::Custom { region: region_name, endpoint: endpoint.clone() }, Some(endpoint))
+            (Region::Custom { region: region_name.clone(), endpoint: endpoint.clone() }, Some(endpoint), Some(region_name))
         }
         "minio" => {
             let endpoint = "http://localhost:9000".to_string();
-            (Region::Custom { region: "us-east-1".to_string(), endpoint: endpoint.clone() }, Some(endpoint))
+            (Region::Custom { region: "us-east-1".to_string(), endpoint: endpoint.clone() }, Some(endpoint), None)
         }
         _ => return Err(format!("不支持的云厂商: {}", cfg.provider)),
     };
 
-    Ok((region, endpoint))
+    Ok((region, endpoint, location))
+}
+
+fn qiniu_s3_region(region: &str) -> String {
+    match region {
+        "z0" => "cn-east-1",
+        "z1" => "cn-north-1",
+        "z2" => "cn-south-1",
+        "na0" => "us-north-1",
+        "as0" => "ap-southeast-1",
+        other => other,
+    }
+    .to_string()
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_ref().filter(|v| !v.trim().is_empty()).cloned()
+}
+
+// 按 id 从保险库读取已保存的 OSS 配置，凭证只在后端内存中流转
+pub(crate) fn load_oss_config(vault: &VaultState, config_id: &str) -> Result<OssConfig, String> {
+    let text = vault
+        .load_record(CLOUD_OSS_CONFIGS_KEY)?
+        .ok_or_else(|| "尚未保存任何 OSS 配置".to_string())?;
+    let configs: Vec<CloudOssConfig> = serde_json::from_str(&text).map_err(|e| format!("OSS 配置格式错误: {}", e))?;
+    let stored = configs
+        .iter()
+        .find(|c| c.id == config_id)
+        .ok_or_else(|| format!("未找到 OSS 配置: {}", config_id))?;
+
+    if stored.auth_type == AuthType::Sts {
+        if let Some(expiration) = non_empty(&stored.expiration) {
+            if let Ok(exp) = DateTime::parse_from_rfc3339(&expiration) {
+                if exp < Utc::now() {
+                    return Err(format!("{} 的 STS 临时凭证已过期", stored.name));
+                }
+            }
+        }
+    }
+
+    Ok(OssConfig {
+        provider: stored.provider.as_str().to_string(),
+        access_key: stored.access_key.clone(),
+        secret_key: stored.secret_key.clone(),
+        region: non_empty(&stored.region),
+        endpoint: non_empty(&stored.endpoint),
+        bucket: non_empty(&stored.bucket),
+        session_token: if stored.auth_type == AuthType::Sts { non_empty(&stored.session_token) } else { None },
+        expiration: non_empty(&stored.expiration),
+    })
+}
+
+fn build_credentials(cfg: &OssConfig) -> Result<Credentials, String> {
+    // 支持STS临时凭证
+    Credentials::new(
+        Some(&cfg.access_key),
+        Some(&cfg.secret_key),
+        cfg.session_token.as_deref(),
+        None,
+        None,
+    )
+    .map_err(redact_error)
 }
 
 fn build_bucket(cfg: &OssConfig) -> Result<Bucket, String> {
@@ -147,141 +281,347 @@ fn build_bucket(cfg: &OssConfig) -> Result<Bucket, String> {
         .clone()
         .ok_or_else(|| "Missing bucket name".to_string())?;
 
-    let (region, _endpoint) = resolve_region_and_endpoint(cfg)?;
-
-    // 支持STS临时凭证
-    let credentials = if let Some(session_token) = &cfg.session_token {
-        // 使用STS临时凭证
-        Credentials::new(
-            Some(&cfg.access_key),
-            Some(&cfg.secret_key),
-            Some(session_token),
-            None,
-            None,
-        )
-        .map_err(|e| e.to_string())?
-    } else {
-        // 使用长期凭证
-        Credentials::new(
-            Some(&cfg.access_key),
-            Some(&cfg.secret_key),
-            None,
-            None,
-            None,
-        )
-        .map_err(|e| e.to_string())?
-    };
+    let (region, _endpoint, _) = resolve_region_and_endpoint(cfg)?;
+    let credentials = build_credentials(cfg)?;
 
-    let mut bucket = Bucket::new(&bucket_name, region, credentials).map_err(|e| e.to_string())?;
+    let mut bucket = Bucket::new(&bucket_name, region, credentials).map_err(redact_error)?;
     // 设置底层请求超时，避免长时间挂起
     bucket.set_request_timeout(Some(Duration::from_secs(12)));
     Ok(bucket)
 }
 
-#[command]
-pub async fn oss_list_buckets(cfg: OssConfig) -> Result<OssResult<Vec<OssBucketSummary>>, String> {
-    // 使用 rust-s3 的关联函数 Bucket::list_buckets(region, creds)
-    let mut cfg_mut = cfg.clone();
-    
-    // 为腾讯云等厂商使用特殊的 ListBuckets 端点
-    let (mut region, _) = match cfg_mut.provider.as_str() {
-        "tencent" => {
-            // 腾讯云 ListBuckets 使用服务端点，需要指定区域
-            let region_name = cfg_mut.region.clone().unwrap_or_else(|| "ap-beijing".to_string());
-            let region = Region::Custom {
-                region: region_name,
-                endpoint: "https://service.cos.myqcloud.com".to_string(),
-            };
-            (region, Some("https://service.cos.myqcloud.com".to_string()))
+// 单个 OSS 操作的超时与重试策略
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    // 单次请求超时
+    pub timeout: Duration,
+    pub timeout_message: &'static str,
+    // 含首次请求在内的最多尝试次数，重定向不计入
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub(crate) const fn new(timeout_secs: u64, timeout_message: &'static str) -> Self {
+        RetryPolicy {
+            timeout: Duration::from_secs(timeout_secs),
+            timeout_message,
+            max_attempts: 4,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
         }
-        "aliyun" => {
-            // 阿里云 ListBuckets 使用服务端点
-            let region = Region::Custom {
-                region: "".to_string(),
-                endpoint: "https://oss.aliyuncs.com".to_string(),
-            };
-            (region, Some("https://oss.aliyuncs.com".to_string()))
+    }
+
+    // 指数退避加全抖动：在 [0, min(max_delay, base_delay * 2^n)] 内随机等待
+    fn backoff(&self, retry: u32) -> Duration {
+        let cap = self.base_delay.saturating_mul(1 << retry.min(16)).min(self.max_delay);
+        Duration::from_millis(rand::thread_rng().gen_range(0..=cap.as_millis() as u64))
+    }
+}
+
+pub(crate) const LIST_POLICY: RetryPolicy = RetryPolicy::new(30, "请求超时，请检查网络或 Endpoint 配置");
+pub(crate) const LIST_BUCKETS_POLICY: RetryPolicy = RetryPolicy::new(12, "请求超时，请检查网络或 Endpoint 配置");
+pub(crate) const UPLOAD_POLICY: RetryPolicy = RetryPolicy::new(20, "上传超时，请检查网络或 Endpoint 配置");
+pub(crate) const DOWNLOAD_POLICY: RetryPolicy = RetryPolicy::new(20, "下载超时，请检查网络或 Endpoint 配置");
+pub(crate) const DELETE_POLICY: RetryPolicy = RetryPolicy::new(12, "删除超时，请检查网络或 Endpoint 配置");
+pub(crate) const CREATE_FOLDER_POLICY: RetryPolicy = RetryPolicy::new(12, "创建文件夹超时，请检查网络或 Endpoint 配置");
+
+// SigV4 预签名链接的有效期上限为 7 天
+const DEFAULT_PRESIGN_EXPIRY_SECS: u32 = 3600;
+const MAX_PRESIGN_EXPIRY_SECS: u32 = 7 * 24 * 3600;
+
+// 同一次操作最多跟随的重定向次数
+const MAX_REDIRECTS: u32 = 2;
+
+// PermanentRedirect 修正得到的 region 与 endpoint，可用于更新已保存的配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EndpointCorrection {
+    pub region: String,
+    pub endpoint: String,
+    // 是否已按设置自动写回保存的配置
+    #[serde(default)]
+    pub saved: bool,
+}
+
+impl EndpointCorrection {
+    // 后续请求直接使用修正后的地址，避免每次都先被重定向
+    pub(crate) fn apply(&self, cfg: &mut OssConfig) {
+        cfg.region = Some(self.region.clone());
+        cfg.endpoint = Some(self.endpoint.clone());
+    }
+}
+
+pub(crate) struct OssOutcome<T> {
+    pub value: T,
+    pub correction: Option<EndpointCorrection>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Failure {
+    // 301 PermanentRedirect：修正后的 endpoint 可以长期使用
+    PermanentRedirect(String),
+    // 307 TemporaryRedirect：仅本次改道，不回写配置
+    TemporaryRedirect(String),
+    Retryable,
+    Fatal,
+}
+
+const RETRYABLE_STATUS: [u16; 5] = [429, 500, 502, 503, 504];
+const RETRYABLE_CODES: [&str; 7] = [
+    "<Code>SlowDown</Code>",
+    "<Code>Throttling</Code>",
+    "<Code>ThrottlingException</Code>",
+    "<Code>RequestTimeout</Code>",
+    "<Code>InternalError</Code>",
+    "<Code>ServiceUnavailable</Code>",
+    "<Code>TooManyRequests</Code>",
+];
+
+fn classify(status: Option<u16>, text: &str) -> Failure {
+    if let Some(ep) = extract_endpoint_from_error_xml(text) {
+        if status == Some(307) || text.contains("<Code>TemporaryRedirect</Code>") {
+            return Failure::TemporaryRedirect(ep);
         }
-        _ => resolve_region_and_endpoint(&cfg_mut)?
-    };
-    
-    let mut credentials = if let Some(session_token) = &cfg_mut.session_token {
-        // 使用STS临时凭证
-        Credentials::new(
-            Some(&cfg_mut.access_key),
-            Some(&cfg_mut.secret_key),
-            Some(session_token),
-            None,
-            None,
-        ).map_err(|e| e.to_string())?
-    } else {
-        // 使用长期凭证
-        Credentials::new(
-            Some(&cfg_mut.access_key),
-            Some(&cfg_mut.secret_key),
-            None,
-            None,
-            None,
-        ).map_err(|e| e.to_string())?
-    };
+        return Failure::PermanentRedirect(ep);
+    }
+    if status.is_some_and(|s| RETRYABLE_STATUS.contains(&s)) || RETRYABLE_CODES.iter().any(|c| text.contains(c)) {
+        return Failure::Retryable;
+    }
+    Failure::Fatal
+}
+
+fn classify_error(e: &S3Error) -> Failure {
+    match e {
+        S3Error::HttpFailWithBody(status, _) => classify(Some(*status), &e.to_string()),
+        // 连接失败、读写超时等网络错误可以重试
+        S3Error::Reqwest(re) if re.is_timeout() || re.is_connect() || re.is_request() => Failure::Retryable,
+        _ => classify(None, &e.to_string()),
+    }
+}
 
-    // 首次尝试
-    let list_res = tokio::time::timeout(
-        Duration::from_secs(12),
-        Bucket::list_buckets(region.clone(), credentials.clone())
-    ).await.map_err(|_| "请求超时，请检查网络或 Endpoint 配置".to_string());
-
-    let list = match list_res {
-        Ok(Ok(resp)) => resp,
-        Ok(Err(e)) => {
-            let err_text = e.to_string();
-            if let Some(ep) = extract_endpoint_from_error_xml(&err_text) {
-                if let Some(new_region) = infer_region_from_endpoint(&cfg_mut.provider, &ep) {
-                    let full_ep = ensure_scheme(&ep);
-                    cfg_mut.endpoint = Some(full_ep.into_owned());
-                    cfg_mut.region = Some(new_region);
-                    // 依据修正后的 region/endpoint 重建 region
-                    region = resolve_region_and_endpoint(&cfg_mut)?.0;
-                    credentials = if let Some(session_token) = &cfg_mut.session_token {
-                        // 使用STS临时凭证
-                        Credentials::new(
-                            Some(&cfg_mut.access_key),
-                            Some(&cfg_mut.secret_key),
-                            Some(session_token),
-                            None,
-                            None,
-                        ).map_err(|e| e.to_string())?
-                    } else {
-                        // 使用长期凭证
-                        Credentials::new(
-                            Some(&cfg_mut.access_key),
-                            Some(&cfg_mut.secret_key),
-                            None,
-                            None,
-                            None,
-                        ).map_err(|e| e.to_string())?
-                    };
-                    tokio::time::timeout(
-                        Duration::from_secs(12),
-                        Bucket::list_buckets(region.clone(), credentials.clone())
-                    ).await.map_err(|_| "请求超时，请检查网络或 Endpoint 配置".to_string())?
-                     .map_err(|e| e.to_string())?
-                } else { return Err(err_text); }
-            } else { return Err(err_text); }
+// 通用执行器：按策略执行一次 OSS 操作，处理超时、重定向与可重试错误。
+// op 每次尝试都会拿到当前（可能已修正）的配置，返回待执行的请求
+pub(crate) async fn execute<T, F, Fut>(cfg: &OssConfig, policy: RetryPolicy, mut op: F) -> Result<OssOutcome<T>, String>
+where
+    F: FnMut(&OssConfig) -> Result<Fut, String>,
+    Fut: Future<Output = Result<T, S3Error>>,
+{
+    let mut current = cfg.clone();
+    let mut correction = None;
+    let mut retries = 0;
+    let mut redirects = 0;
+    loop {
+        let (failure, err_text) = match tokio::time::timeout(policy.timeout, op(&current)?).await {
+            Ok(Ok(value)) => return Ok(OssOutcome { value, correction }),
+            Ok(Err(e)) => (classify_error(&e), redact_error(&e)),
+            Err(_) => (Failure::Retryable, policy.timeout_message.to_string()),
+        };
+        match failure {
+            Failure::PermanentRedirect(ep) | Failure::TemporaryRedirect(ep) if redirects >= MAX_REDIRECTS => {
+                warn!("too many redirects, last endpoint {}", ep);
+                return Err(err_text);
+            }
+            Failure::PermanentRedirect(ep) => {
+                let Some(region) = infer_region_from_endpoint(&current.provider, &ep) else { return Err(err_text) };
+                let endpoint = ensure_scheme(&ep).into_owned();
+                debug!(region = %region, "permanent redirect, endpoint corrected");
+                current.endpoint = Some(endpoint.clone());
+                current.region = Some(region.clone());
+                correction = Some(EndpointCorrection { region, endpoint, saved: false });
+                redirects += 1;
+            }
+            Failure::TemporaryRedirect(ep) if !endpoint_in_provider_domain(&current.provider, &ep) => {
+                warn!("redirect to endpoint outside provider domain: {}", ep);
+                return Err(err_text);
+            }
+            Failure::TemporaryRedirect(ep) => {
+                debug!("temporary redirect");
+                if let Some(region) = infer_region_from_endpoint(&current.provider, &ep) {
+                    current.region = Some(region);
+                }
+                current.endpoint = Some(ensure_scheme(&ep).into_owned());
+                redirects += 1;
+            }
+            Failure::Retryable if retries + 1 < policy.max_attempts => {
+                let delay = policy.backoff(retries);
+                retries += 1;
+                debug!(attempt = retries, delay_ms = delay.as_millis() as u64, "retrying: {}", err_text);
+                tokio::time::sleep(delay).await;
+            }
+            Failure::Retryable | Failure::Fatal => return Err(err_text),
         }
-        Err(e) => { return Err(e); }
+    }
+}
+
+// 针对单个 Bucket 的操作，底层请求超时与策略保持一致
+pub(crate) async fn execute_on_bucket<T, F, Fut>(cfg: &OssConfig, policy: RetryPolicy, mut op: F) -> Result<OssOutcome<T>, String>
+where
+    F: FnMut(Bucket) -> Fut,
+    Fut: Future<Output = Result<T, S3Error>>,
+{
+    execute(cfg, policy, |current| {
+        let mut bucket = build_bucket(current)?;
+        bucket.set_request_timeout(Some(policy.timeout));
+        Ok(op(bucket))
+    })
+    .await
+}
+
+// rust-s3 未提供的请求（子资源、批量删除等）按 SigV4 自行签名发送
+pub(crate) struct SignedRequest<'a> {
+    pub method: Method,
+    // 为空时访问存储桶本身
+    pub key: Option<&'a str>,
+    pub query: &'a [(&'a str, &'a str)],
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+pub(crate) struct RawResponse {
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+pub(crate) fn signed_client() -> Result<&'static reqwest::Client, String> {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    // 重定向交给 execute 处理
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(12))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(redact_error)?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+// 对象 key 编码为 URL 路径。Url::parse 会合并 . 与 .. 路径段（写成 %2E 也一样），
+// 因此把这类段与相邻段之间的 / 编码为 %2F，服务端解码后仍是原来的 key
+fn key_path(key: &str) -> Result<String, String> {
+    let is_dot = |s: &str| s == "." || s == "..";
+    if is_dot(key) {
+        return Err(format!("对象 key 不能为 {}", key));
+    }
+    let segments: Vec<&str> = key.split('/').collect();
+    let mut path = String::with_capacity(key.len());
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            let joins_dot = is_dot(segments[i - 1]) || (is_dot(segment) && i + 1 == segments.len());
+            path.push_str(if joins_dot { "%2F" } else { "/" });
+        }
+        path.push_str(&uri_encode(segment, false));
+    }
+    Ok(path)
+}
+
+// 与 rust-s3 一致使用虚拟主机风格：https://<bucket>.<host>/<key>
+fn signed_url(cfg: &OssConfig, region: &Region, request: &SignedRequest) -> Result<Url, String> {
+    let bucket_name = cfg.bucket.as_deref().ok_or_else(|| "Missing bucket name".to_string())?;
+    let mut url = format!(
+        "{}://{}.{}/{}",
+        region.scheme(),
+        bucket_name,
+        region.host(),
+        key_path(request.key.unwrap_or_default())?
+    );
+    if !request.query.is_empty() {
+        let query: Vec<String> = request
+            .query
+            .iter()
+            .map(|(k, v)| if v.is_empty() { uri_encode(k, false) } else { format!("{}={}", uri_encode(k, false), uri_encode(v, false)) })
+            .collect();
+        url = format!("{}?{}", url, query.join("&"));
+    }
+    Url::parse(&url).map_err(|e| format!("请求地址无效: {}", e))
+}
+
+// 匿名访问检查等不签名的请求使用同样的地址
+pub(crate) fn request_url(cfg: &OssConfig, request: &SignedRequest) -> Result<Url, String> {
+    let (region, _, _) = resolve_region_and_endpoint(cfg)?;
+    signed_url(cfg, &region, request)
+}
+
+// 每次尝试都按当前（可能已被重定向修正的）配置重新签名
+fn prepare_signed(cfg: &OssConfig, request: &SignedRequest) -> Result<(Url, HeaderMap), String> {
+    let (region, _, _) = resolve_region_and_endpoint(cfg)?;
+    let url = signed_url(cfg, &region, request)?;
+    let mut headers = request.headers.clone();
+    let keys = SigningKeys {
+        access_key: &cfg.access_key,
+        secret_key: &cfg.secret_key,
+        session_token: cfg.session_token.as_deref(),
+        region: &region.to_string(),
     };
+    sign(request.method.as_str(), &url, &mut headers, &payload_hash(&request.body), &keys, Utc::now())?;
+    Ok((url, headers))
+}
+
+async fn send_signed(client: &reqwest::Client, url: Url, headers: HeaderMap, request: &SignedRequest<'_>) -> Result<RawResponse, S3Error> {
+    let response = client
+        .request(request.method.clone(), url)
+        .headers(headers)
+        .body(request.body.clone())
+        .send()
+        .await?;
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    let body = response.text().await?;
+    if !(200..300).contains(&status) {
+        return Err(S3Error::HttpFailWithBody(status, body));
+    }
+    Ok(RawResponse { headers, body })
+}
+
+pub(crate) async fn execute_signed(cfg: &OssConfig, policy: RetryPolicy, request: &SignedRequest<'_>) -> Result<OssOutcome<RawResponse>, String> {
+    let client = signed_client()?;
+    execute(cfg, policy, |current| {
+        let (url, headers) = prepare_signed(current, request)?;
+        Ok(send_signed(client, url, headers, request))
+    })
+    .await
+}
+
+// ListBuckets 所用的服务端点；发生重定向修正后按修正结果访问
+fn list_buckets_region(cfg: &OssConfig, corrected: bool) -> Result<Region, String> {
+    if corrected {
+        return Ok(resolve_region_and_endpoint(cfg)?.0);
+    }
+    match cfg.provider.as_str() {
+        // 腾讯云 ListBuckets 使用服务端点，需要指定区域
+        "tencent" => Ok(Region::Custom {
+            region: cfg.region.clone().unwrap_or_else(|| "ap-beijing".to_string()),
+            endpoint: "https://service.cos.myqcloud.com".to_string(),
+        }),
+        // 阿里云 ListBuckets 使用服务端点
+        "aliyun" => Ok(Region::Custom {
+            region: "".to_string(),
+            endpoint: "https://oss.aliyuncs.com".to_string(),
+        }),
+        _ => Ok(resolve_region_and_endpoint(cfg)?.0),
+    }
+}
+
+#[command]
+pub async fn oss_list_buckets(cfg: OssConfig) -> Result<OssResult<Vec<OssBucketSummary>>, String> {
+    // 使用 rust-s3 的关联函数 Bucket::list_buckets(region, creds)
+    let outcome = execute(&cfg, LIST_BUCKETS_POLICY, |current| {
+        let corrected = current.endpoint != cfg.endpoint;
+        Ok(Bucket::list_buckets(list_buckets_region(current, corrected)?, build_credentials(current)?))
+    })
+    .await?;
 
     // 将结果映射
     let mut buckets: Vec<OssBucketSummary> = Vec::new();
-    for b in list.buckets.bucket.into_iter() { // BucketContainer 持有 bucket 向量
+    for b in outcome.value.buckets.bucket.into_iter() { // BucketContainer 持有 bucket 向量
         buckets.push(OssBucketSummary {
             name: b.name,
             creation_date: Some(b.creation_date),
         });
     }
 
-    Ok(OssResult { success: true, data: Some(buckets), message: None, error: None })
+    Ok(OssResult { success: true, data: Some(buckets), message: None, error: None, correction: outcome.correction })
 }
 
 fn extract_endpoint_from_error_xml(err_text: &str) -> Option<String> {
@@ -295,17 +635,46 @@ fn extract_endpoint_from_error_xml(err_text: &str) -> Option<String> {
     Some(endpoint.to_string())
 }
 
+// 各厂商服务地址的域名后缀，重定向与修正只接受这些域名下的 endpoint
+fn provider_domain(provider: &str) -> Option<&'static str> {
+    match provider {
+        "aws" => Some(".amazonaws.com"),
+        "aliyun" => Some(".aliyuncs.com"),
+        "tencent" => Some(".myqcloud.com"),
+        "huawei" => Some(".myhuaweicloud.com"),
+        "jdcloud" => Some(".jdcloud-oss.com"),
+        "ksyun" => Some(".ksyuncs.com"),
+        "qingcloud" => Some(".qingstor.com"),
+        "qiniu" => Some(".qiniucs.com"),
+        _ => None,
+    }
+}
+
+// 解析出 endpoint 的主机名（输入可能是带 schema 的 URL 或 host）
+fn endpoint_host(endpoint: &str) -> Option<String> {
+    Url::parse(&ensure_scheme(endpoint)).ok()?.host_str().map(|h| h.to_ascii_lowercase())
+}
+
+// 有已知域名的厂商要求主机名以该域名结尾；未登记的厂商（自建服务等）不做限制
+fn endpoint_in_provider_domain(provider: &str, endpoint: &str) -> bool {
+    match (provider_domain(provider), endpoint_host(endpoint)) {
+        (Some(domain), Some(host)) => host.ends_with(domain),
+        (None, Some(_)) => true,
+        (_, None) => false,
+    }
+}
+
 fn infer_region_from_endpoint(provider: &str, endpoint: &str) -> Option<String> {
-    // 输入可能是带 schema 的 URL 或 host
-    let host = if let Some(pos) = endpoint.find("://") { &endpoint[pos + 3..] } else { endpoint };
-    let host = host.split('/').next().unwrap_or(host);
+    let host = endpoint_host(endpoint)?;
+    if provider_domain(provider).is_some_and(|domain| !host.ends_with(domain)) {
+        return None;
+    }
+    let host = host.as_str();
     match provider {
         "aliyun" => {
-            // oss-cn-xxx.aliyuncs.com => oss-cn-xxx
-            let prefix = "oss-";
-            let suffix = ".aliyuncs.com";
-            if let (Some(p), Some(s)) = (host.find(prefix), host.find(suffix)) { if s > p { return Some(host[p..s].to_string()); } }
-            None
+            // [bucket.]oss-cn-xxx.aliyuncs.com => oss-cn-xxx
+            let label = host.strip_suffix(".aliyuncs.com")?.rsplit('.').next()?;
+            label.starts_with("oss-").then(|| label.to_string())
         }
         "tencent" => {
             // cos.<region>.myqcloud.com
@@ -350,203 +719,229 @@ fn ensure_scheme(endpoint: &str) -> Cow<'_, str> {
 }
 
 #[command]
-pub async fn oss_list_objects(cfg: OssConfig, params: Option<OssListParams>) -> Result<OssResult<Vec<OssFileItem>>, String> {
-    println!("oss_list_objects called with config: provider={}, bucket={}, region={}, endpoint={}", 
-             cfg.provider, 
-             cfg.bucket.as_deref().unwrap_or("None"), 
-             cfg.region.as_deref().unwrap_or("None"),
-             cfg.endpoint.as_deref().unwrap_or("None"));
-    
-    let mut cfg_mut = cfg.clone();
-    let mut bucket = build_bucket(&cfg_mut).map_err(|e| {
-        println!("build_bucket failed: {}", e);
-        e
-    })?;
+pub async fn oss_list_objects(cfg: OssConfig, params: Option<OssListParams>) -> Result<OssResult<OssListPage>, String> {
+    debug!(provider = %cfg.provider, "oss_list_objects");
 
     let prefix = params.as_ref().and_then(|p| p.prefix.clone()).unwrap_or_default();
     let delimiter = params
         .as_ref()
         .and_then(|p| p.delimiter.clone())
         .unwrap_or_else(|| "/".to_string());
+    let continuation_token = params.as_ref().and_then(|p| non_empty(&p.continuation_token));
+    let start_after = params.as_ref().and_then(|p| non_empty(&p.start_after));
+    let max_keys = params
+        .as_ref()
+        .and_then(|p| p.max_keys)
+        .unwrap_or(MAX_KEYS_PER_PAGE)
+        .clamp(1, MAX_KEYS_PER_PAGE);
 
-    println!("Starting list request with prefix='{}', delimiter='{}'", prefix, delimiter);
-    let result = match tokio::time::timeout(Duration::from_secs(30), bucket.list(prefix.clone(), Some(delimiter.clone()))).await {
-        Err(_) => {
-            println!("List request timed out after 30 seconds");
-            return Err("请求超时，请检查网络或 Endpoint 配置".to_string())
-        },
-        Ok(res) => match res {
-        Ok(ok) => ok,
-        Err(e) => {
-            let err_text = e.to_string();
-            if let Some(ep) = extract_endpoint_from_error_xml(&err_text) {
-                if let Some(region) = infer_region_from_endpoint(&cfg_mut.provider, &ep) {
-                    let full_ep = ensure_scheme(&ep);
-                    cfg_mut.endpoint = Some(full_ep.into_owned());
-                    cfg_mut.region = Some(region);
-                    bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-                    tokio::time::timeout(Duration::from_secs(30), bucket.list(prefix.clone(), Some(delimiter))).await.map_err(|_| "请求超时，请检查网络或 Endpoint 配置".to_string())?.map_err(|e| e.to_string())?
-                } else { return Err(err_text); }
-            } else { return Err(err_text); }
-        }
-    }};
-
-    let mut items: Vec<OssFileItem> = Vec::new();
-    if let Some(first) = result.get(0) {
-        if let Some(prefix_group) = &first.common_prefixes {
-            for cp in prefix_group {
-                let key = cp.prefix.clone();
-                items.push(OssFileItem {
-                    key,
-                    size: 0,
-                    last_modified: None,
-                    is_directory: true,
-                });
-            }
-        }
-        for content in &first.contents {
-            // rust-s3 returns RFC3339-ish or RFC2822 strings; try parse flexibly
-            let lm_str = content.last_modified.clone();
-            let last_modified = DateTime::parse_from_rfc2822(&lm_str)
-                .map(|dt| DateTime::<Utc>::from(dt).to_rfc3339())
-                .or_else(|_| DateTime::parse_from_rfc3339(&lm_str).map(|dt| dt.to_rfc3339()))
-                .ok();
-
-            items.push(OssFileItem {
-                key: content.key.clone(),
-                size: content.size as u64,
-                last_modified,
-                is_directory: false,
-            });
-        }
-    }
+    debug!(prefix = %prefix, delimiter = %delimiter, max_keys, "starting list request");
+    let outcome = list_objects_page(&cfg, &prefix, &delimiter, continuation_token.as_deref(), start_after.as_deref(), max_keys)
+        .await
+        .inspect_err(|e| warn!("list request failed: {}", e))?;
+    let page = outcome.value;
 
     Ok(OssResult {
         success: true,
-        data: Some(items),
+        data: Some(OssListPage {
+            items: page.items,
+            next_token: page.next_token,
+            next_start_after: page.start_after,
+            is_truncated: page.is_truncated,
+        }),
         message: None,
         error: None,
+        correction: outcome.correction,
     })
 }
 
-#[command]
-pub async fn oss_upload_file(cfg: OssConfig, params: OssUploadParams) -> Result<OssResult<String>, String> {
-    let mut cfg_mut = cfg.clone();
-    let mut bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-    
-    let key = if let Some(path) = &params.path {
-        if path.is_empty() {
-            params.file_name.clone()
-        } else {
-            format!("{}/{}", path.trim_end_matches('/'), params.file_name)
+// 一页列举结果。is_truncated 取自服务端的 IsTruncated；
+// 截断但没有 V2 continuation token 时，start_after 为继续列举的起点
+pub(crate) struct ListPage {
+    pub items: Vec<OssFileItem>,
+    pub is_truncated: bool,
+    pub next_token: Option<String>,
+    pub start_after: Option<String>,
+}
+
+// rust-s3 的 list_page 不请求 fetch-owner，也不解析 ChecksumAlgorithm，这里直接发送 ListObjectsV2
+pub(crate) async fn list_objects_page(
+    cfg: &OssConfig,
+    prefix: &str,
+    delimiter: &str,
+    continuation_token: Option<&str>,
+    start_after: Option<&str>,
+    max_keys: u32,
+) -> Result<OssOutcome<ListPage>, String> {
+    let max_keys = max_keys.to_string();
+    let mut query = vec![("list-type", "2"), ("fetch-owner", "true"), ("prefix", prefix), ("max-keys", max_keys.as_str())];
+    if !delimiter.is_empty() {
+        query.push(("delimiter", delimiter));
+    }
+    if let Some(token) = continuation_token {
+        query.push(("continuation-token", token));
+    } else if let Some(after) = start_after {
+        // 只支持 V1 的厂商忽略 start-after，同时带上 marker
+        query.push(("start-after", after));
+        query.push(("marker", after));
+    }
+    let request = SignedRequest { method: Method::GET, key: None, query: &query, headers: HeaderMap::new(), body: Vec::new() };
+    let outcome = execute_signed(cfg, LIST_POLICY, &request).await?;
+    Ok(OssOutcome { value: parse_list_page(&outcome.value.body), correction: outcome.correction })
+}
+
+// 递归列出前缀下的全部对象（包括以 / 结尾的目录占位对象），用于批量复制、移动和删除
+pub(crate) async fn list_all_objects(cfg: &OssConfig, prefix: &str) -> Result<OssOutcome<Vec<OssFileItem>>, String> {
+    let mut cfg = cfg.clone();
+    let mut correction = None;
+    let mut items = Vec::new();
+    let mut token: Option<String> = None;
+    let mut start_after: Option<String> = None;
+    loop {
+        let outcome = list_objects_page(&cfg, prefix, "", token.as_deref(), start_after.as_deref(), MAX_KEYS_PER_PAGE).await?;
+        if let Some(c) = outcome.correction {
+            c.apply(&mut cfg);
+            correction = Some(c);
+        }
+        let page = outcome.value;
+        items.extend(page.items);
+        if !page.is_truncated {
+            break;
         }
+        // 分页标记不变说明服务端忽略了它，继续请求只会重复同一页；
+        // 结果不完整时直接报错，避免按不完整的清单复制或删除
+        match (page.next_token, page.start_after) {
+            (Some(next), _) if token.as_ref() != Some(&next) => {
+                token = Some(next);
+                start_after = None;
+            }
+            (None, Some(after)) if start_after.as_ref() != Some(&after) => {
+                token = None;
+                start_after = Some(after);
+            }
+            _ => return Err("列举结果不完整：服务端提示还有更多对象，但分页标记缺失或未推进".to_string()),
+        }
+    }
+    Ok(OssOutcome { value: items, correction })
+}
+
+// 解析 ListObjectsV2 响应：目录（CommonPrefixes）在前，文件在后
+fn parse_list_page(body: &str) -> ListPage {
+    let mut items: Vec<OssFileItem> = Vec::new();
+    for cp in xml::blocks(body, "CommonPrefixes") {
+        let Some(prefix) = xml::text(cp, "Prefix") else { continue };
+        items.push(OssFileItem {
+            key: prefix,
+            size: 0,
+            last_modified: None,
+            is_directory: true,
+            etag: None,
+            storage_class: None,
+            owner_id: None,
+            owner_display_name: None,
+            checksum_algorithm: None,
+        });
+    }
+    for content in xml::blocks(body, "Contents") {
+        let Some(key) = xml::text(content, "Key") else { continue };
+        // 多数厂商返回 ISO 8601，兼容 RFC2822
+        let last_modified = xml::text(content, "LastModified").and_then(|lm| {
+            DateTime::parse_from_rfc3339(&lm)
+                .or_else(|_| DateTime::parse_from_rfc2822(&lm))
+                .map(|dt| DateTime::<Utc>::from(dt).to_rfc3339())
+                .ok()
+        });
+        let owner = xml::blocks(content, "Owner").first().copied().unwrap_or_default();
+        items.push(OssFileItem {
+            key,
+            size: xml::text(content, "Size").and_then(|v| v.parse().ok()).unwrap_or(0),
+            last_modified,
+            is_directory: false,
+            etag: xml::text(content, "ETag"),
+            storage_class: xml::text(content, "StorageClass"),
+            owner_id: xml::text(owner, "ID"),
+            owner_display_name: xml::text(owner, "DisplayName"),
+            checksum_algorithm: xml::text(content, "ChecksumAlgorithm"),
+        });
+    }
+
+    // V2 返回 NextContinuationToken；按 V1 返回时用 NextMarker，没有则取本页最后一个文件 key 继续。
+    // 不能用目录前缀：带分隔符时从 prefix/ 之后继续会再次列出同一个目录
+    let is_truncated = xml::text(body, "IsTruncated").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    let next_token = xml::text(body, "NextContinuationToken").filter(|t| is_truncated && !t.is_empty());
+    let start_after = if is_truncated && next_token.is_none() {
+        xml::text(body, "NextMarker")
+            .filter(|m| !m.is_empty())
+            .or_else(|| items.iter().rev().find(|i| !i.is_directory).map(|i| i.key.clone()))
     } else {
-        params.file_name.clone()
+        None
     };
+    ListPage { items, is_truncated, next_token, start_after }
+}
+
+// 目标目录加文件名得到对象 key
+pub(crate) fn upload_key(path: Option<&str>, file_name: &str) -> String {
+    match path {
+        Some(path) if !path.is_empty() => format!("{}/{}", path.trim_end_matches('/'), file_name),
+        _ => file_name.to_string(),
+    }
+}
 
-    let content_type = params.content_type.unwrap_or_else(|| {
+#[command]
+pub async fn oss_upload_file(cfg: OssConfig, params: OssUploadParams) -> Result<OssResult<String>, String> {
+    let key = upload_key(params.path.as_deref(), &params.file_name);
+
+    let content_type = params.content_type.clone().unwrap_or_else(|| {
         mime_guess::from_path(&params.file_name)
             .first_or_octet_stream()
             .to_string()
     });
 
-    match tokio::time::timeout(Duration::from_secs(20), bucket
-        .put_object_with_content_type(&key, &params.file_data, &content_type))
-        .await
-    {
-        Err(_) => return Err("上传超时，请检查网络或 Endpoint 配置".to_string()),
-        Ok(Ok(_)) => {}
-        Ok(Err(e)) => {
-            let err_text = e.to_string();
-            if let Some(ep) = extract_endpoint_from_error_xml(&err_text) {
-                if let Some(region) = infer_region_from_endpoint(&cfg_mut.provider, &ep) {
-                    let full_ep = ensure_scheme(&ep);
-                    cfg_mut.endpoint = Some(full_ep.into_owned());
-                    cfg_mut.region = Some(region);
-                    bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-                    tokio::time::timeout(Duration::from_secs(20), bucket
-                        .put_object_with_content_type(&key, &params.file_data, &content_type))
-                        .await
-                        .map_err(|_| "上传超时，请检查网络或 Endpoint 配置".to_string())?
-                        .map_err(|e| e.to_string())?;
-                } else { return Err(err_text); }
-            } else { return Err(err_text); }
-        }
-    }
+    let (key_ref, data, content_type) = (&key, &params.file_data, &content_type);
+    let outcome = execute_on_bucket(&cfg, UPLOAD_POLICY, |bucket| async move {
+        bucket.put_object_with_content_type(key_ref, data, content_type).await
+    })
+    .await?;
 
     Ok(OssResult {
         success: true,
         data: Some(key),
         message: Some("文件上传成功".to_string()),
         error: None,
+        correction: outcome.correction,
     })
 }
 
 #[command]
 pub async fn oss_download_file(cfg: OssConfig, params: OssDownloadParams) -> Result<OssResult<Vec<u8>>, String> {
-    let mut cfg_mut = cfg.clone();
-    let mut bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-    
-    let result = match tokio::time::timeout(Duration::from_secs(20), bucket.get_object(&params.key)).await {
-        Err(_) => return Err("下载超时，请检查网络或 Endpoint 配置".to_string()),
-        Ok(Ok(r)) => r,
-        Ok(Err(e)) => {
-            let err_text = e.to_string();
-            if let Some(ep) = extract_endpoint_from_error_xml(&err_text) {
-                if let Some(region) = infer_region_from_endpoint(&cfg_mut.provider, &ep) {
-                    let full_ep = ensure_scheme(&ep);
-                    cfg_mut.endpoint = Some(full_ep.into_owned());
-                    cfg_mut.region = Some(region);
-                    bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-                    tokio::time::timeout(Duration::from_secs(20), bucket.get_object(&params.key)).await.map_err(|_| "下载超时，请检查网络或 Endpoint 配置".to_string())?.map_err(|e| e.to_string())?
-                } else { return Err(err_text); }
-            } else { return Err(err_text); }
-        }
-    };
+    let key = &params.key;
+    let outcome = execute_on_bucket(&cfg, DOWNLOAD_POLICY, |bucket| async move { bucket.get_object(key).await }).await?;
 
     Ok(OssResult {
         success: true,
-        data: Some(result.bytes().to_vec()),
+        data: Some(outcome.value.bytes().to_vec()),
         message: Some("文件下载成功".to_string()),
         error: None,
+        correction: outcome.correction,
     })
 }
 
 #[command]
 pub async fn oss_delete_file(cfg: OssConfig, params: OssDeleteParams) -> Result<OssResult<String>, String> {
-    let mut cfg_mut = cfg.clone();
-    let mut bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-    
-    match tokio::time::timeout(Duration::from_secs(12), bucket.delete_object(&params.key)).await {
-        Err(_) => return Err("删除超时，请检查网络或 Endpoint 配置".to_string()),
-        Ok(Ok(_)) => {}
-        Ok(Err(e)) => {
-            let err_text = e.to_string();
-            if let Some(ep) = extract_endpoint_from_error_xml(&err_text) {
-                if let Some(region) = infer_region_from_endpoint(&cfg_mut.provider, &ep) {
-                    let full_ep = ensure_scheme(&ep);
-                    cfg_mut.endpoint = Some(full_ep.into_owned());
-                    cfg_mut.region = Some(region);
-                    bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-                    tokio::time::timeout(Duration::from_secs(12), bucket.delete_object(&params.key)).await.map_err(|_| "删除超时，请检查网络或 Endpoint 配置".to_string())?.map_err(|e| e.to_string())?;
-                } else { return Err(err_text); }
-            } else { return Err(err_text); }
-        }
-    }
+    let key = &params.key;
+    let outcome = execute_on_bucket(&cfg, DELETE_POLICY, |bucket| async move { bucket.delete_object(key).await }).await?;
 
     Ok(OssResult {
         success: true,
         data: Some(params.key),
         message: Some("文件删除成功".to_string()),
         error: None,
+        correction: outcome.correction,
     })
 }
 
 #[command]
 pub async fn oss_create_folder(cfg: OssConfig, params: OssCreateFolderParams) -> Result<OssResult<String>, String> {
-    let mut cfg_mut = cfg.clone();
-    let mut bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-    
     let folder_key = if let Some(path) = &params.path {
         if path.is_empty() {
             format!("{}/", params.folder_name)
@@ -558,32 +953,370 @@ pub async fn oss_create_folder(cfg: OssConfig, params: OssCreateFolderParams) ->
     };
 
     // 创建一个空文件来模拟文件夹
-    match tokio::time::timeout(Duration::from_secs(12), bucket.put_object(&folder_key, b"")).await {
-        Err(_) => return Err("创建文件夹超时，请检查网络或 Endpoint 配置".to_string()),
-        Ok(Ok(_)) => {}
-        Ok(Err(e)) => {
-            let err_text = e.to_string();
-            if let Some(ep) = extract_endpoint_from_error_xml(&err_text) {
-                if let Some(region) = infer_region_from_endpoint(&cfg_mut.provider, &ep) {
-                    let full_ep = ensure_scheme(&ep);
-                    cfg_mut.endpoint = Some(full_ep.into_owned());
-                    cfg_mut.region = Some(region);
-                    bucket = build_bucket(&cfg_mut).map_err(|e| e.to_string())?;
-                    tokio::time::timeout(Duration::from_secs(12), bucket.put_object(&folder_key, b""))
-                        .await
-                        .map_err(|_| "创建文件夹超时，请检查网络或 Endpoint 配置".to_string())?
-                        .map_err(|e| e.to_string())?;
-                } else { return Err(err_text); }
-            } else { return Err(err_text); }
-        }
-    }
+    let key = &folder_key;
+    let outcome = execute_on_bucket(&cfg, CREATE_FOLDER_POLICY, |bucket| async move { bucket.put_object(key, b"").await }).await?;
 
     Ok(OssResult {
         success: true,
         data: Some(folder_key),
         message: Some("文件夹创建成功".to_string()),
         error: None,
+        correction: outcome.correction,
     })
 }
 
+// 需要签入链接的 response-* 查询参数，下载时由服务端按其覆盖响应头
+fn presign_response_overrides(params: &OssPresignParams) -> HashMap<String, String> {
+    [
+        ("response-content-type", &params.response_content_type),
+        ("response-content-disposition", &params.response_content_disposition),
+        ("response-cache-control", &params.response_cache_control),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Some((name.to_string(), non_empty(value)?)))
+    .collect()
+}
+
+// 链接的实际失效时间：STS 临时凭证过期后链接随之失效
+fn presign_expires_at(now: DateTime<Utc>, expires_secs: u32, sts_expiration: Option<&str>) -> DateTime<Utc> {
+    let expires_at = now + chrono::Duration::seconds(expires_secs as i64);
+    sts_expiration
+        .and_then(|exp| DateTime::parse_from_rfc3339(exp).ok())
+        .map(|exp| exp.with_timezone(&Utc).min(expires_at))
+        .unwrap_or(expires_at)
+}
+
+// 生成限时访问链接，签名在本地完成，不发起网络请求
+#[command]
+pub async fn oss_presign(cfg: OssConfig, params: OssPresignParams) -> Result<OssResult<OssPresignedUrl>, String> {
+    let method = params.method.as_deref().unwrap_or("GET").to_ascii_uppercase();
+    let expires_secs = params.expires_secs.unwrap_or(DEFAULT_PRESIGN_EXPIRY_SECS);
+    if !(1..=MAX_PRESIGN_EXPIRY_SECS).contains(&expires_secs) {
+        return Err(format!("有效期需在 1 ~ {} 秒之间", MAX_PRESIGN_EXPIRY_SECS));
+    }
+    let overrides = presign_response_overrides(&params);
+
+    let bucket = build_bucket(&cfg)?;
+    let url = match method.as_str() {
+        "GET" => {
+            let queries = (!overrides.is_empty()).then_some(overrides);
+            bucket.presign_get(&params.key, expires_secs, queries).await
+        }
+        "PUT" if overrides.is_empty() => bucket.presign_put(&params.key, expires_secs, None).await,
+        "PUT" => return Err("响应头覆盖仅适用于 GET 链接".to_string()),
+        _ => return Err(format!("不支持的方法: {}（可选 GET/PUT）", method)),
+    }
+    .map_err(redact_error)?;
+
+    let now = Utc::now();
+    let expires_at = presign_expires_at(now, expires_secs, cfg.expiration.as_deref());
+    if expires_at <= now {
+        return Err("STS 临时凭证已过期".to_string());
+    }
+    let message = if expires_at < now + chrono::Duration::seconds(expires_secs as i64) {
+        "链接已生成，将随 STS 临时凭证提前失效"
+    } else {
+        "链接已生成"
+    };
+
+    Ok(OssResult {
+        success: true,
+        data: Some(OssPresignedUrl {
+            url,
+            method,
+            expires_at: expires_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }),
+        message: Some(message.to_string()),
+        error: None,
+        correction: None,
+    })
+}
 
+// 把 PermanentRedirect 修正后的 region/endpoint 写回已保存的配置，校验与推断规则一致
+pub(crate) fn save_endpoint_correction(vault: &VaultState, config_id: &str, correction: &EndpointCorrection) -> Result<(), String> {
+    let text = vault
+        .load_record(CLOUD_OSS_CONFIGS_KEY)?
+        .ok_or_else(|| "尚未保存任何 OSS 配置".to_string())?;
+    // 按原始 JSON 修改，保留其余字段不变
+    let mut configs: Vec<Value> = serde_json::from_str(&text).map_err(|e| format!("OSS 配置格式错误: {}", e))?;
+    let stored = configs
+        .iter_mut()
+        .find(|c| c.get("id").and_then(Value::as_str) == Some(config_id))
+        .ok_or_else(|| format!("未找到 OSS 配置: {}", config_id))?;
+
+    let provider = stored.get("provider").and_then(Value::as_str).unwrap_or_default();
+    if provider_domain(provider).is_none() || !endpoint_in_provider_domain(provider, &correction.endpoint) {
+        return Err("修正后的 endpoint 不在该厂商的服务域名下".to_string());
+    }
+    if infer_region_from_endpoint(provider, &correction.endpoint).as_deref() != Some(correction.region.as_str()) {
+        return Err("修正后的 region 与 endpoint 不匹配".to_string());
+    }
+    let Some(obj) = stored.as_object_mut() else { return Err("OSS 配置格式错误".to_string()) };
+    obj.insert("region".to_string(), Value::String(correction.region.clone()));
+    obj.insert("endpoint".to_string(), Value::String(correction.endpoint.clone()));
+    obj.insert(
+        "updatedAt".to_string(),
+        Value::String(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+    );
+
+    let text = Zeroizing::new(serde_json::to_string(&configs).map_err(|e| e.to_string())?);
+    vault.store_record(CLOUD_OSS_CONFIGS_KEY, &text)
+}
+
+// 后端请求中实际遇到、尚未写回的修正，按配置 id 保存；前端只能确认应用，不能提交任意 endpoint
+fn pending_corrections() -> &'static Mutex<HashMap<String, EndpointCorrection>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, EndpointCorrection>>> = OnceLock::new();
+    PENDING.get_or_init(Default::default)
+}
+
+// 设置允许时自动写回修正结果，否则暂存，交由前端征得用户同意后调用 oss_apply_endpoint_correction
+pub(crate) fn remember_correction<T>(vault: &VaultState, config_id: &str, result: &mut OssResult<T>) {
+    let Some(correction) = result.correction.as_mut() else { return };
+    if !load_settings().auto_apply_endpoint_correction {
+        if let Ok(mut pending) = pending_corrections().lock() {
+            pending.insert(config_id.to_string(), correction.clone());
+        }
+        return;
+    }
+    match save_endpoint_correction(vault, config_id, correction) {
+        Ok(()) => correction.saved = true,
+        Err(e) => warn!("failed to save endpoint correction: {}", e),
+    }
+}
+
+// 写回该配置最近一次暂存的修正
+#[command]
+pub fn oss_apply_endpoint_correction(vault: State<'_, VaultState>, config_id: String) -> Result<EndpointCorrection, String> {
+    let mut pending = pending_corrections().lock().map_err(|_| "修正状态异常".to_string())?;
+    let correction = pending
+        .get(&config_id)
+        .cloned()
+        .ok_or_else(|| "没有待应用的 endpoint 修正".to_string())?;
+    save_endpoint_correction(&vault, &config_id, &correction)?;
+    pending.remove(&config_id);
+    Ok(EndpointCorrection { saved: true, ..correction })
+}
+
+// 按配置 id 列举对象，前端无需持有 secret_key
+#[command]
+pub async fn oss_list_objects_by_id(vault: State<'_, VaultState>, config_id: String, params: Option<OssListParams>) -> Result<OssResult<OssListPage>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_list_objects(cfg, params).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[command]
+pub async fn oss_list_buckets_by_id(vault: State<'_, VaultState>, config_id: String) -> Result<OssResult<Vec<OssBucketSummary>>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_list_buckets(cfg).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[command]
+pub async fn oss_upload_file_by_id(vault: State<'_, VaultState>, config_id: String, params: OssUploadParams) -> Result<OssResult<String>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_upload_file(cfg, params).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[command]
+pub async fn oss_download_file_by_id(vault: State<'_, VaultState>, config_id: String, params: OssDownloadParams) -> Result<OssResult<Vec<u8>>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_download_file(cfg, params).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[command]
+pub async fn oss_delete_file_by_id(vault: State<'_, VaultState>, config_id: String, params: OssDeleteParams) -> Result<OssResult<String>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_delete_file(cfg, params).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[command]
+pub async fn oss_create_folder_by_id(vault: State<'_, VaultState>, config_id: String, params: OssCreateFolderParams) -> Result<OssResult<String>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = oss_create_folder(cfg, params).await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[command]
+pub async fn oss_presign_by_id(vault: State<'_, VaultState>, config_id: String, params: OssPresignParams) -> Result<OssResult<OssPresignedUrl>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    oss_presign(cfg, params).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_redirects() {
+        let body = "<Error><Code>PermanentRedirect</Code><Endpoint>oss-cn-shanghai.aliyuncs.com</Endpoint></Error>";
+        assert_eq!(classify(Some(301), body), Failure::PermanentRedirect("oss-cn-shanghai.aliyuncs.com".to_string()));
+
+        let body = "<Error><Code>TemporaryRedirect</Code><Endpoint>b.s3-us-west-2.amazonaws.com</Endpoint></Error>";
+        assert_eq!(classify(Some(307), body), Failure::TemporaryRedirect("b.s3-us-west-2.amazonaws.com".to_string()));
+    }
+
+    #[test]
+    fn infers_region_only_within_provider_domain() {
+        assert_eq!(infer_region_from_endpoint("aliyun", "https://oss-cn-hangzhou.aliyuncs.com").as_deref(), Some("oss-cn-hangzhou"));
+        assert_eq!(infer_region_from_endpoint("aliyun", "b.oss-cn-hangzhou.aliyuncs.com").as_deref(), Some("oss-cn-hangzhou"));
+        assert_eq!(infer_region_from_endpoint("aliyun", "https://oss-cn-hangzhou.aliyuncs.com.evil.com"), None);
+        assert_eq!(infer_region_from_endpoint("tencent", "cos.ap-guangzhou.myqcloud.com.evil.com"), None);
+        assert!(!endpoint_in_provider_domain("aliyun", "https://evil-aliyuncs.com"));
+        assert!(endpoint_in_provider_domain("aws", "b.s3-us-west-2.amazonaws.com"));
+    }
+
+    #[test]
+    fn classifies_retryable_and_fatal() {
+        assert_eq!(classify(Some(503), "<Error><Code>SlowDown</Code></Error>"), Failure::Retryable);
+        assert_eq!(classify(Some(400), "<Error><Code>RequestTimeout</Code></Error>"), Failure::Retryable);
+        assert_eq!(classify(Some(403), "<Error><Code>AccessDenied</Code></Error>"), Failure::Fatal);
+        assert_eq!(classify(Some(404), "<Error><Code>NoSuchKey</Code></Error>"), Failure::Fatal);
+    }
+
+    #[test]
+    fn presign_overrides_and_sts_expiry() {
+        let params = OssPresignParams {
+            key: "a.txt".to_string(),
+            method: None,
+            expires_secs: None,
+            response_content_type: Some("text/plain".to_string()),
+            response_content_disposition: Some(" ".to_string()),
+            response_cache_control: None,
+        };
+        let overrides = presign_response_overrides(&params);
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides["response-content-type"], "text/plain");
+
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let sts = "2024-01-01T00:30:00Z";
+        assert_eq!(presign_expires_at(now, 3600, Some(sts)).to_rfc3339(), "2024-01-01T00:30:00+00:00");
+        assert_eq!(presign_expires_at(now, 600, Some(sts)).to_rfc3339(), "2024-01-01T00:10:00+00:00");
+        assert_eq!(presign_expires_at(now, 600, None).to_rfc3339(), "2024-01-01T00:10:00+00:00");
+    }
+
+    #[test]
+    fn parses_list_objects_v2() {
+        let body = r#"<ListBucketResult><Name>b</Name><KeyCount>2</KeyCount><IsTruncated>true</IsTruncated>
+<NextContinuationToken>tok</NextContinuationToken>
+<Contents><Key>docs/a&amp;b.txt</Key><LastModified>2024-01-02T03:04:05.000Z</LastModified><ETag>&quot;abc&quot;</ETag>
+<ChecksumAlgorithm>CRC32</ChecksumAlgorithm><Size>12</Size><Owner><ID>o1</ID><DisplayName>ops</DisplayName></Owner>
+<StorageClass>GLACIER</StorageClass></Contents>
+<CommonPrefixes><Prefix>docs/sub/</Prefix></CommonPrefixes></ListBucketResult>"#;
+        let page = parse_list_page(body);
+        assert!(page.is_truncated);
+        assert_eq!(page.next_token.as_deref(), Some("tok"));
+        assert_eq!(page.start_after, None);
+        let items = page.items;
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_directory && items[0].key == "docs/sub/");
+        let file = &items[1];
+        assert_eq!(file.key, "docs/a&b.txt");
+        assert_eq!(file.size, 12);
+        assert_eq!(file.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(file.storage_class.as_deref(), Some("GLACIER"));
+        assert_eq!(file.owner_display_name.as_deref(), Some("ops"));
+        assert_eq!(file.checksum_algorithm.as_deref(), Some("CRC32"));
+        assert_eq!(file.last_modified.as_deref(), Some("2024-01-02T03:04:05+00:00"));
+
+        let page = parse_list_page("<ListBucketResult><IsTruncated>false</IsTruncated></ListBucketResult>");
+        assert!(!page.is_truncated && page.next_token.is_none() && page.start_after.is_none());
+
+        // 截断但没有 continuation token：按本页最后一个 key 继续
+        let page = parse_list_page(
+            "<ListBucketResult><IsTruncated>TRUE</IsTruncated><Contents><Key>a</Key></Contents><Contents><Key>b</Key></Contents></ListBucketResult>",
+        );
+        assert!(page.is_truncated);
+        assert_eq!(page.next_token, None);
+        assert_eq!(page.start_after.as_deref(), Some("b"));
+
+        // 带分隔符的 V1 响应：优先 NextMarker，没有时跳过目录前缀取最后一个文件
+        let page = parse_list_page(
+            "<ListBucketResult><IsTruncated>true</IsTruncated><Marker></Marker><NextMarker>z/</NextMarker>\
+<Contents><Key>a.txt</Key></Contents><CommonPrefixes><Prefix>z/</Prefix></CommonPrefixes></ListBucketResult>",
+        );
+        assert_eq!(page.start_after.as_deref(), Some("z/"));
+        let page = parse_list_page(
+            "<ListBucketResult><IsTruncated>true</IsTruncated>\
+<Contents><Key>a.txt</Key></Contents><CommonPrefixes><Prefix>z/</Prefix></CommonPrefixes></ListBucketResult>",
+        );
+        assert_eq!(page.start_after.as_deref(), Some("a.txt"));
+    }
+
+    #[test]
+    fn keeps_dot_segments_in_signed_keys() {
+        assert_eq!(key_path("a b/c.txt").unwrap(), "a%20b/c.txt");
+        assert_eq!(key_path("x/./y").unwrap(), "x/.%2Fy");
+        assert_eq!(key_path("a/..").unwrap(), "a%2F..");
+        assert!(key_path("..").is_err());
+
+        let url = Url::parse(&format!("https://b.s3.amazonaws.com/{}", key_path("a/../b.txt").unwrap())).unwrap();
+        assert_eq!(url.path(), "/a/..%2Fb.txt");
+        let keys = SigningKeys { access_key: "ak", secret_key: "sk", session_token: None, region: "us-east-1" };
+        let mut headers = HeaderMap::new();
+        sign("HEAD", &url, &mut headers, &payload_hash(b""), &keys, Utc::now()).unwrap();
+        assert!(headers.contains_key(reqwest::header::AUTHORIZATION));
+        assert_eq!(url.path(), "/a/..%2Fb.txt");
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = RetryPolicy::new(1, "");
+        for retry in 0..40 {
+            assert!(policy.backoff(retry) <= policy.max_delay);
+        }
+    }
+
+    #[tokio::test]
+    async fn executor_retries_then_succeeds() {
+        let cfg = OssConfig {
+            provider: "aliyun".to_string(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            region: None,
+            endpoint: None,
+            bucket: None,
+            session_token: None,
+            expiration: None,
+        };
+        let mut policy = RetryPolicy::new(1, "timeout");
+        policy.base_delay = Duration::from_millis(1);
+        let mut calls = 0;
+        let outcome = execute(&cfg, policy, |current| {
+            calls += 1;
+            let attempt = calls;
+            let endpoint = current.endpoint.clone();
+            Ok(async move {
+                match attempt {
+                    1 => Err(S3Error::HttpFailWithBody(503, "<Code>SlowDown</Code>".to_string())),
+                    2 => Err(S3Error::HttpFailWithBody(
+                        301,
+                        "<Code>PermanentRedirect</Code><Endpoint>oss-cn-shanghai.aliyuncs.com</Endpoint>".to_string(),
+                    )),
+                    _ => Ok(endpoint),
+                }
+            })
+        })
+        .await
+        .unwrap();
+        assert_eq!(calls, 3);
+        assert_eq!(outcome.value.as_deref(), Some("https://oss-cn-shanghai.aliyuncs.com"));
+        assert_eq!(
+            outcome.correction,
+            Some(EndpointCorrection {
+                region: "oss-cn-shanghai".to_string(),
+                endpoint: "https://oss-cn-shanghai.aliyuncs.com".to_string(),
+                saved: false,
+            })
+        );
+    }
+}
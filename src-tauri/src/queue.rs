@@ -0,0 +1,479 @@
+use crate::download::{discard_partial, download_to_path, OssDownloadPathParams};
+use crate::oss::{load_oss_config, remember_correction};
+use crate::storage::{atomic_write, data_dir, ensure_data_dir};
+use crate::transfer::{ActiveTransfers, TransferSignal};
+use crate::upload::{abort_upload, upload_path, OssUploadPathParams};
+use crate::vault::VaultState;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Manager, State};
+use tracing::warn;
+
+// 传输队列明文保存（只有配置 id、对象 key 与本地路径，不含凭证）；不使用 .json 扩展名，避免被当作数据键
+const QUEUE_FILE: &str = "transfers.conf";
+const TRANSFER_EVENT: &str = "transfer-updated";
+pub(crate) const MAX_CONCURRENT_TRANSFERS: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TransferRequest {
+    Upload(OssUploadPathParams),
+    Download(OssDownloadPathParams),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Cancelled)
+    }
+
+    // 排队、运行或暂停中的任务占用本地文件；失败的任务不占用，可以重新添加
+    fn holds_file(self) -> bool {
+        matches!(self, JobStatus::Queued | JobStatus::Running | JobStatus::Paused)
+    }
+}
+
+// 除 except 外是否还有任务占用该本地文件
+fn path_in_use(jobs: &[TransferJob], local_path: &str, except: Option<&str>) -> bool {
+    jobs.iter()
+        .any(|job| job.status.holds_file() && job.local_path() == local_path && Some(job.id.as_str()) != except)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferJob {
+    pub id: String,
+    // 按已保存配置的 id 执行，凭证在开始传输时才从保险库读取
+    pub config_id: String,
+    pub request: TransferRequest,
+    pub status: JobStatus,
+    pub transferred_bytes: u64,
+    pub total_bytes: u64,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl TransferJob {
+    fn local_path(&self) -> &str {
+        match &self.request {
+            TransferRequest::Upload(params) => &params.local_path,
+            TransferRequest::Download(params) => &params.local_path,
+        }
+    }
+
+    fn set_status(&mut self, status: JobStatus, error: Option<String>) {
+        self.status = status;
+        self.error = error;
+        self.updated_at = Utc::now().to_rfc3339();
+    }
+}
+
+// 后台传输队列：全局并发上限，单个任务可暂停、继续、取消，重启后恢复
+pub struct TransferManager {
+    jobs: Mutex<Vec<TransferJob>>,
+    signals: Mutex<HashMap<String, TransferSignal>>,
+    max_concurrent: AtomicUsize,
+}
+
+fn queue_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join(QUEUE_FILE))
+}
+
+// 上次退出时仍在运行的任务重新排队，上传凭断点中的 upload_id、下载凭 .part 文件续传
+fn recover_jobs(mut jobs: Vec<TransferJob>) -> Vec<TransferJob> {
+    for job in jobs.iter_mut().filter(|job| job.status == JobStatus::Running) {
+        job.status = JobStatus::Queued;
+    }
+    jobs
+}
+
+// 按入队顺序挑出可以开始的任务
+fn runnable_jobs(jobs: &[TransferJob], max_concurrent: usize) -> Vec<usize> {
+    let running = jobs.iter().filter(|job| job.status == JobStatus::Running).count();
+    jobs.iter()
+        .enumerate()
+        .filter(|(_, job)| job.status == JobStatus::Queued)
+        .map(|(i, _)| i)
+        .take(max_concurrent.saturating_sub(running))
+        .collect()
+}
+
+impl TransferManager {
+    pub fn load(max_concurrent: usize) -> Self {
+        let jobs = queue_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .map(recover_jobs)
+            .unwrap_or_default();
+        TransferManager {
+            jobs: Mutex::new(jobs),
+            signals: Mutex::new(HashMap::new()),
+            max_concurrent: AtomicUsize::new(max_concurrent.clamp(1, MAX_CONCURRENT_TRANSFERS)),
+        }
+    }
+
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        self.max_concurrent
+            .store(max_concurrent.clamp(1, MAX_CONCURRENT_TRANSFERS), Ordering::Relaxed);
+    }
+
+    fn lock_jobs(&self) -> Result<std::sync::MutexGuard<'_, Vec<TransferJob>>, String> {
+        self.jobs.lock().map_err(|_| "传输队列状态异常".to_string())
+    }
+
+    fn save(jobs: &[TransferJob]) {
+        let result = ensure_data_dir()
+            .and_then(|_| serde_json::to_string_pretty(jobs).map_err(|e| e.to_string()))
+            .and_then(|text| atomic_write(&queue_path()?, text.as_bytes()));
+        if let Err(e) = result {
+            warn!("保存传输队列失败: {}", e);
+        }
+    }
+
+    // 修改一个任务；persist 为 true 时写回队列文件，随后通知前端
+    fn update(
+        &self,
+        app: &AppHandle,
+        id: &str,
+        persist: bool,
+        f: impl FnOnce(&mut TransferJob) -> Result<(), String>,
+    ) -> Result<TransferJob, String> {
+        let job = {
+            let mut jobs = self.lock_jobs()?;
+            let job = jobs
+                .iter_mut()
+                .find(|job| job.id == id)
+                .ok_or_else(|| format!("未找到传输任务: {}", id))?;
+            f(job)?;
+            let job = job.clone();
+            if persist {
+                Self::save(&jobs);
+            }
+            job
+        };
+        let _ = app.emit_all(TRANSFER_EVENT, job.clone());
+        Ok(job)
+    }
+
+    fn enqueue(&self, app: &AppHandle, config_id: String, request: TransferRequest) -> Result<TransferJob, String> {
+        let now = Utc::now().to_rfc3339();
+        let job = TransferJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            config_id,
+            request,
+            status: JobStatus::Queued,
+            transferred_bytes: 0,
+            total_bytes: 0,
+            error: None,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        {
+            let mut jobs = self.lock_jobs()?;
+            // 同一本地文件同时只能有一个进行中的任务
+            if path_in_use(&jobs, job.local_path(), None) {
+                return Err(format!("该文件已在传输队列中: {}", job.local_path()));
+            }
+            jobs.push(job.clone());
+            Self::save(&jobs);
+        }
+        let _ = app.emit_all(TRANSFER_EVENT, job.clone());
+        self.schedule(app);
+        Ok(job)
+    }
+
+    // 在并发上限内启动排队中的任务；保险库锁定时不启动，解锁后由定时检查补上
+    pub fn schedule(&self, app: &AppHandle) {
+        if !app.state::<VaultState>().is_unlocked() {
+            return;
+        }
+        let started: Vec<TransferJob> = {
+            let Ok(mut jobs) = self.jobs.lock() else { return };
+            let indexes = runnable_jobs(&jobs, self.max_concurrent.load(Ordering::Relaxed));
+            if indexes.is_empty() {
+                return;
+            }
+            for &i in &indexes {
+                jobs[i].set_status(JobStatus::Running, None);
+            }
+            Self::save(&jobs);
+            indexes.into_iter().map(|i| jobs[i].clone()).collect()
+        };
+        for job in started {
+            let signal = TransferSignal::default();
+            if let Ok(mut signals) = self.signals.lock() {
+                signals.insert(job.id.clone(), signal.clone());
+            }
+            let _ = app.emit_all(TRANSFER_EVENT, job.clone());
+            tauri::async_runtime::spawn(run_job(app.clone(), job, signal));
+        }
+    }
+
+    fn signal(&self, id: &str) -> Option<TransferSignal> {
+        self.signals.lock().ok()?.get(id).cloned()
+    }
+
+    fn finish(&self, app: &AppHandle, id: &str, signal: &TransferSignal, result: Result<(), String>) {
+        if let Ok(mut signals) = self.signals.lock() {
+            signals.remove(id);
+        }
+        let _ = self.update(app, id, true, |job| {
+            match result {
+                Ok(()) => {
+                    job.transferred_bytes = job.total_bytes;
+                    job.set_status(JobStatus::Completed, None);
+                }
+                Err(_) if signal.is_paused() => job.set_status(JobStatus::Paused, None),
+                Err(_) if signal.is_cancelled() => job.set_status(JobStatus::Cancelled, None),
+                Err(e) => job.set_status(JobStatus::Failed, Some(e)),
+            }
+            Ok(())
+        });
+    }
+}
+
+async fn execute_job(app: &AppHandle, job: &TransferJob, signal: &TransferSignal) -> Result<(), String> {
+    let vault = app.state::<VaultState>();
+    let active = app.state::<ActiveTransfers>();
+    let manager = app.state::<TransferManager>();
+    let cfg = load_oss_config(&vault, &job.config_id)?;
+    match &job.request {
+        TransferRequest::Upload(params) => {
+            // 第一次拿到 upload_id 时写回队列，暂停或重启后凭它从断点续传
+            let upload_id_saved = AtomicBool::new(params.upload_id.is_some());
+            let mut result = upload_path(&cfg, params, &active, signal, |progress| {
+                let persist = !progress.upload_id.is_empty() && !upload_id_saved.swap(true, Ordering::Relaxed);
+                let _ = manager.update(app, &job.id, persist, |job| {
+                    if let (true, TransferRequest::Upload(params)) = (persist, &mut job.request) {
+                        params.upload_id = Some(progress.upload_id.clone());
+                    }
+                    job.transferred_bytes = progress.uploaded_bytes;
+                    job.total_bytes = progress.total_bytes;
+                    Ok(())
+                });
+            })
+            .await?;
+            remember_correction(&vault, &job.config_id, &mut result);
+        }
+        TransferRequest::Download(params) => {
+            let mut result = download_to_path(&cfg, params, &active, signal, |progress| {
+                let _ = manager.update(app, &job.id, false, |job| {
+                    job.transferred_bytes = progress.downloaded_bytes;
+                    job.total_bytes = progress.total_bytes;
+                    Ok(())
+                });
+            })
+            .await?;
+            remember_correction(&vault, &job.config_id, &mut result);
+        }
+    }
+    Ok(())
+}
+
+async fn run_job(app: AppHandle, job: TransferJob, signal: TransferSignal) {
+    let result = execute_job(&app, &job, &signal).await;
+    let manager = app.state::<TransferManager>();
+    manager.finish(&app, &job.id, &signal, result);
+    manager.schedule(&app);
+}
+
+// 清理未在运行的任务留下的断点：中止服务端分片上传、删除下载临时文件
+async fn discard_job(vault: &VaultState, active: &ActiveTransfers, job: &TransferJob) {
+    match &job.request {
+        TransferRequest::Upload(params) => {
+            let Some(upload_id) = &params.upload_id else { return };
+            let result = match load_oss_config(vault, &job.config_id) {
+                Ok(cfg) => abort_upload(&cfg, active, upload_id).await.map(|_| ()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("中止分片上传失败: {}", e);
+            }
+        }
+        TransferRequest::Download(params) => discard_partial(&params.local_path),
+    }
+}
+
+#[command]
+pub fn transfer_enqueue_upload(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    manager: State<'_, TransferManager>,
+    config_id: String,
+    params: OssUploadPathParams,
+) -> Result<TransferJob, String> {
+    load_oss_config(&vault, &config_id)?;
+    if !PathBuf::from(&params.local_path).is_file() {
+        return Err(format!("不是文件: {}", params.local_path));
+    }
+    manager.enqueue(&app, config_id, TransferRequest::Upload(params))
+}
+
+#[command]
+pub fn transfer_enqueue_download(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    manager: State<'_, TransferManager>,
+    config_id: String,
+    params: OssDownloadPathParams,
+) -> Result<TransferJob, String> {
+    load_oss_config(&vault, &config_id)?;
+    if PathBuf::from(&params.local_path).exists() && !params.overwrite.unwrap_or(false) {
+        return Err(format!("本地文件已存在: {}", params.local_path));
+    }
+    manager.enqueue(&app, config_id, TransferRequest::Download(params))
+}
+
+#[command]
+pub fn transfer_list(manager: State<'_, TransferManager>) -> Result<Vec<TransferJob>, String> {
+    Ok(manager.lock_jobs()?.clone())
+}
+
+// 排队中的任务直接暂停；运行中的任务在当前分片或数据块结束后暂停，状态由任务结束时更新
+#[command]
+pub fn transfer_pause(app: AppHandle, manager: State<'_, TransferManager>, id: String) -> Result<TransferJob, String> {
+    if let Some(signal) = manager.signal(&id) {
+        signal.pause();
+        return manager.update(&app, &id, false, |_| Ok(()));
+    }
+    manager.update(&app, &id, true, |job| match job.status {
+        JobStatus::Queued => {
+            job.set_status(JobStatus::Paused, None);
+            Ok(())
+        }
+        JobStatus::Paused => Ok(()),
+        _ => Err("只能暂停排队中或正在传输的任务".to_string()),
+    })
+}
+
+// 继续已暂停或失败的任务，重新排队
+#[command]
+pub fn transfer_resume(app: AppHandle, manager: State<'_, TransferManager>, id: String) -> Result<TransferJob, String> {
+    {
+        // 失败后同一文件可能已重新添加了任务，此时不再重试旧任务
+        let jobs = manager.lock_jobs()?;
+        if let Some(job) = jobs.iter().find(|job| job.id == id && job.status == JobStatus::Failed) {
+            if path_in_use(&jobs, job.local_path(), Some(&id)) {
+                return Err(format!("该文件已有新的传输任务: {}", job.local_path()));
+            }
+        }
+    }
+    // 运行中的任务已收到暂停信号时，结束后会被标记为暂停，此时继续会被覆盖，需等暂停完成
+    let pausing = manager.signal(&id).is_some_and(|signal| signal.is_paused());
+    let job = manager.update(&app, &id, true, |job| match job.status {
+        JobStatus::Paused | JobStatus::Failed => {
+            job.set_status(JobStatus::Queued, None);
+            Ok(())
+        }
+        JobStatus::Running if pausing => Err("任务正在暂停，请稍后再继续".to_string()),
+        JobStatus::Queued | JobStatus::Running => Ok(()),
+        _ => Err("任务已结束，无法继续".to_string()),
+    })?;
+    manager.schedule(&app);
+    Ok(job)
+}
+
+#[command]
+pub async fn transfer_cancel(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    active: State<'_, ActiveTransfers>,
+    manager: State<'_, TransferManager>,
+    id: String,
+) -> Result<TransferJob, String> {
+    // 运行中的任务自行中止并清理断点
+    if let Some(signal) = manager.signal(&id) {
+        signal.cancel();
+        return manager.update(&app, &id, false, |_| Ok(()));
+    }
+    let job = manager.update(&app, &id, true, |job| {
+        if job.status.is_finished() {
+            return Err("任务已结束".to_string());
+        }
+        job.set_status(JobStatus::Cancelled, None);
+        Ok(())
+    })?;
+    discard_job(&vault, &active, &job).await;
+    Ok(job)
+}
+
+// 移除已完成和已取消的任务
+#[command]
+pub fn transfer_clear_finished(manager: State<'_, TransferManager>) -> Result<Vec<TransferJob>, String> {
+    let mut jobs = manager.lock_jobs()?;
+    jobs.retain(|job| !job.status.is_finished());
+    TransferManager::save(&jobs);
+    Ok(jobs.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: &str, status: JobStatus) -> TransferJob {
+        TransferJob {
+            id: id.to_string(),
+            config_id: "cfg".to_string(),
+            request: TransferRequest::Download(OssDownloadPathParams {
+                key: id.to_string(),
+                local_path: format!("/tmp/{}", id),
+                overwrite: None,
+            }),
+            status,
+            transferred_bytes: 0,
+            total_bytes: 0,
+            error: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn schedules_in_order_within_limit() {
+        let jobs = vec![
+            job("a", JobStatus::Running),
+            job("b", JobStatus::Paused),
+            job("c", JobStatus::Queued),
+            job("d", JobStatus::Queued),
+            job("e", JobStatus::Queued),
+        ];
+        assert_eq!(runnable_jobs(&jobs, 3), vec![2, 3]);
+        assert!(runnable_jobs(&jobs, 1).is_empty());
+    }
+
+    #[test]
+    fn failed_jobs_do_not_block_the_file() {
+        let jobs = vec![job("a", JobStatus::Failed), job("b", JobStatus::Paused)];
+        assert!(!path_in_use(&jobs, "/tmp/a", None));
+        assert!(path_in_use(&jobs, "/tmp/b", None));
+        assert!(!path_in_use(&jobs, "/tmp/b", Some("b")));
+    }
+
+    #[test]
+    fn requeues_interrupted_jobs_after_restart() {
+        let jobs = recover_jobs(vec![job("a", JobStatus::Running), job("b", JobStatus::Paused)]);
+        assert_eq!(jobs[0].status, JobStatus::Queued);
+        assert_eq!(jobs[1].status, JobStatus::Paused);
+
+        let text = serde_json::to_string(&jobs[0]).unwrap();
+        assert!(text.contains(r#""kind":"download""#) && text.contains(r#""status":"queued""#));
+        let parsed: TransferJob = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed.local_path(), "/tmp/a");
+    }
+}
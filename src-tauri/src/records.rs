@@ -0,0 +1,495 @@
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+// 数据键名
+pub const CLOUD_OSS_CONFIGS_KEY: &str = "cloud_oss_configs";
+
+// 存储文档的当前结构版本
+// v1：直接存储记录数组（旧版本前端写入的格式）
+// v2：{ "schemaVersion": 2, "records": [...] }，并补齐时间戳、认证方式等字段
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredDocument {
+    pub schema_version: u32,
+    pub records: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CloudProvider {
+    Aliyun,
+    Tencent,
+    Huawei,
+    Qiniu,
+    Jdcloud,
+    Ksyun,
+    Qingcloud,
+    Aws,
+    Minio,
+}
+
+impl CloudProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloudProvider::Aliyun => "aliyun",
+            CloudProvider::Tencent => "tencent",
+            CloudProvider::Huawei => "huawei",
+            CloudProvider::Qiniu => "qiniu",
+            CloudProvider::Jdcloud => "jdcloud",
+            CloudProvider::Ksyun => "ksyun",
+            CloudProvider::Qingcloud => "qingcloud",
+            CloudProvider::Aws => "aws",
+            CloudProvider::Minio => "minio",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthType {
+    Longterm,
+    Sts,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WeChatAppType {
+    Miniprogram,
+    Official,
+}
+
+// 云厂商OSS配置
+#[derive(Debug, Serialize, Deserialize, Clone, Zeroize, ZeroizeOnDrop)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudOssConfig {
+    pub id: String,
+    pub name: String,
+    #[zeroize(skip)]
+    pub provider: CloudProvider,
+    #[zeroize(skip)]
+    pub auth_type: AuthType,
+    pub access_key: String,
+    pub secret_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 微信小程序 / 公众号
+#[derive(Debug, Serialize, Deserialize, Clone, Zeroize, ZeroizeOnDrop)]
+#[serde(rename_all = "camelCase")]
+pub struct WeChatMiniProgram {
+    pub id: String,
+    pub name: String,
+    pub app_id: String,
+    pub app_secret: String,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    #[zeroize(skip)]
+    pub app_type: Option<WeChatAppType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 企业微信
+#[derive(Debug, Serialize, Deserialize, Clone, Zeroize, ZeroizeOnDrop)]
+#[serde(rename_all = "camelCase")]
+pub struct EnterpriseWeChat {
+    pub id: String,
+    pub name: String,
+    pub corp_id: String,
+    pub corp_secret: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 飞书
+#[derive(Debug, Serialize, Deserialize, Clone, Zeroize, ZeroizeOnDrop)]
+#[serde(rename_all = "camelCase")]
+pub struct Feishu {
+    pub id: String,
+    pub name: String,
+    pub app_id: String,
+    pub app_secret: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 钉钉
+#[derive(Debug, Serialize, Deserialize, Clone, Zeroize, ZeroizeOnDrop)]
+#[serde(rename_all = "camelCase")]
+pub struct DingTalk {
+    pub id: String,
+    pub name: String,
+    pub app_key: String,
+    pub app_secret: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 各类记录共用的校验入口
+pub trait Record: Serialize + DeserializeOwned {
+    fn id(&self) -> &str;
+    fn name(&self) -> &str;
+    // 必填的凭证字段（字段名, 值）
+    fn required_fields(&self) -> Vec<(&'static str, &str)>;
+
+    fn validate(&self) -> Result<(), String> {
+        if self.id().trim().is_empty() {
+            return Err("缺少 id".to_string());
+        }
+        if self.name().trim().is_empty() {
+            return Err("名称不能为空".to_string());
+        }
+        for (field, value) in self.required_fields() {
+            if value.trim().is_empty() {
+                return Err(format!("{} 不能为空", field));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Record for CloudOssConfig {
+    fn id(&self) -> &str { &self.id }
+    fn name(&self) -> &str { &self.name }
+    fn required_fields(&self) -> Vec<(&'static str, &str)> {
+        let mut fields = vec![("accessKey", self.access_key.as_str()), ("secretKey", self.secret_key.as_str())];
+        if self.auth_type == AuthType::Sts {
+            fields.push(("sessionToken", self.session_token.as_deref().unwrap_or("")));
+        }
+        fields
+    }
+}
+
+impl Record for WeChatMiniProgram {
+    fn id(&self) -> &str { &self.id }
+    fn name(&self) -> &str { &self.name }
+    fn required_fields(&self) -> Vec<(&'static str, &str)> {
+        vec![("appId", &self.app_id), ("appSecret", &self.app_secret)]
+    }
+}
+
+impl Record for EnterpriseWeChat {
+    fn id(&self) -> &str { &self.id }
+    fn name(&self) -> &str { &self.name }
+    fn required_fields(&self) -> Vec<(&'static str, &str)> {
+        vec![("corpId", &self.corp_id), ("corpSecret", &self.corp_secret)]
+    }
+}
+
+impl Record for Feishu {
+    fn id(&self) -> &str { &self.id }
+    fn name(&self) -> &str { &self.name }
+    fn required_fields(&self) -> Vec<(&'static str, &str)> {
+        vec![("appId", &self.app_id), ("appSecret", &self.app_secret)]
+    }
+}
+
+impl Record for DingTalk {
+    fn id(&self) -> &str { &self.id }
+    fn name(&self) -> &str { &self.name }
+    fn required_fields(&self) -> Vec<(&'static str, &str)> {
+        vec![("appKey", &self.app_key), ("appSecret", &self.app_secret)]
+    }
+}
+
+// 数据键对应的记录类型，未登记的键按原样存储，不做结构校验
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    CloudOss,
+    WeChat,
+    Enterprise,
+    Feishu,
+    DingTalk,
+}
+
+impl RecordKind {
+    pub fn for_key(key: &str) -> Option<Self> {
+        match key {
+            CLOUD_OSS_CONFIGS_KEY => Some(RecordKind::CloudOss),
+            "sensitive_wechat" => Some(RecordKind::WeChat),
+            "sensitive_enterprise" => Some(RecordKind::Enterprise),
+            "sensitive_feishu" => Some(RecordKind::Feishu),
+            "sensitive_dingtalk" => Some(RecordKind::DingTalk),
+            _ => None,
+        }
+    }
+}
+
+// 反序列化为强类型记录并逐条校验，返回规范化后的 JSON
+fn validate_typed<T: Record>(records: Value) -> Result<Value, String> {
+    let items: Vec<Value> = match records {
+        Value::Array(items) => items,
+        _ => return Err("数据应为记录列表".to_string()),
+    };
+
+    let mut ids = HashSet::new();
+    let mut typed = Vec::with_capacity(items.len());
+    for (i, item) in items.into_iter().enumerate() {
+        let record: T = serde_json::from_value(item).map_err(|e| format!("第 {} 条记录格式错误: {}", i + 1, e))?;
+        record.validate().map_err(|e| format!("第 {} 条记录无效: {}", i + 1, e))?;
+        if !ids.insert(record.id().to_string()) {
+            return Err(format!("第 {} 条记录的 id 重复: {}", i + 1, record.id()));
+        }
+        typed.push(record);
+    }
+    serde_json::to_value(typed).map_err(|e| e.to_string())
+}
+
+pub fn validate_records(kind: Option<RecordKind>, records: Value) -> Result<Value, String> {
+    match kind {
+        Some(RecordKind::CloudOss) => validate_typed::<CloudOssConfig>(records),
+        Some(RecordKind::WeChat) => validate_typed::<WeChatMiniProgram>(records),
+        Some(RecordKind::Enterprise) => validate_typed::<EnterpriseWeChat>(records),
+        Some(RecordKind::Feishu) => validate_typed::<Feishu>(records),
+        Some(RecordKind::DingTalk) => validate_typed::<DingTalk>(records),
+        None => Ok(records),
+    }
+}
+
+type Migration = fn(Option<RecordKind>, Value) -> Result<Value, String>;
+
+// MIGRATIONS[n] 把 v(n+1) 的记录升级为 v(n+2)
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+// v1 -> v2：补齐缺失的时间戳；旧版 OSS 配置没有 authType，按是否带 sessionToken 推断
+fn migrate_v1_to_v2(kind: Option<RecordKind>, records: Value) -> Result<Value, String> {
+    let Some(kind) = kind else { return Ok(records) };
+    let Value::Array(mut items) = records else {
+        return Err("数据应为记录列表".to_string());
+    };
+
+    let now = Utc::now().to_rfc3339();
+    for item in items.iter_mut() {
+        let Some(obj) = item.as_object_mut() else { continue };
+        if !obj.contains_key("createdAt") {
+            obj.insert("createdAt".to_string(), Value::String(now.clone()));
+        }
+        if !obj.contains_key("updatedAt") {
+            let created = obj.get("createdAt").cloned().unwrap_or_else(|| Value::String(now.clone()));
+            obj.insert("updatedAt".to_string(), created);
+        }
+        if kind == RecordKind::CloudOss && !obj.contains_key("authType") {
+            let has_token = obj
+                .get("sessionToken")
+                .and_then(|v| v.as_str())
+                .map(|s| !s.is_empty())
+                .unwrap_or(false);
+            obj.insert("authType".to_string(), Value::String(if has_token { "sts" } else { "longterm" }.to_string()));
+        }
+    }
+    Ok(Value::Array(items))
+}
+
+// 解析已存储的文档：识别版本、依次执行迁移并校验，返回 (记录, 是否发生了迁移)
+pub fn decode_document(key: &str, text: &str) -> Result<(Value, bool), String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("{} 的数据不是有效的 JSON: {}", key, e))?;
+    let (version, mut records) = match value {
+        Value::Object(ref obj) if obj.contains_key("schemaVersion") => {
+            let doc: StoredDocument =
+                serde_json::from_value(value).map_err(|e| format!("{} 的数据格式错误: {}", key, e))?;
+            (doc.schema_version, doc.records)
+        }
+        other => (1, other),
+    };
+
+    if version == 0 || version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "{} 的数据版本为 v{}，当前程序仅支持到 v{}，请升级程序",
+            key, version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    let kind = RecordKind::for_key(key);
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        records = migration(kind, records).map_err(|e| format!("{} 的数据迁移失败: {}", key, e))?;
+    }
+    let records = validate_records(kind, records).map_err(|e| format!("{} 的数据无效: {}", key, e))?;
+    Ok((records, version != CURRENT_SCHEMA_VERSION))
+}
+
+// 校验前端提交的记录并封装为当前版本的存储文档
+pub fn encode_document(key: &str, text: &str) -> Result<String, String> {
+    let records: Value = serde_json::from_str(text).map_err(|e| format!("{} 的数据不是有效的 JSON: {}", key, e))?;
+    let records = validate_records(RecordKind::for_key(key), records).map_err(|e| format!("{} 的数据无效: {}", key, e))?;
+    serde_json::to_string(&StoredDocument { schema_version: CURRENT_SCHEMA_VERSION, records }).map_err(|e| e.to_string())
+}
+
+// Access Key 只保留首尾各 4 位
+fn mask_access_key(access_key: &str) -> String {
+    let chars: Vec<char> = access_key.chars().collect();
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    format!("{}****{}", chars[..4].iter().collect::<String>(), chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+// 返回给前端的 OSS 配置不含凭证：Access Key 脱敏，Secret Key 置空，去掉 STS token；
+// 前端只凭配置 id 调用 *_by_id 命令，凭证留在后端
+pub fn redact_oss_configs(text: &str) -> Result<String, String> {
+    let mut records: Value = serde_json::from_str(text).map_err(|e| format!("OSS 配置格式错误: {}", e))?;
+    for item in records.as_array_mut().into_iter().flatten() {
+        let Some(obj) = item.as_object_mut() else { continue };
+        let masked = obj.get("accessKey").and_then(Value::as_str).map(mask_access_key);
+        if let Some(masked) = masked {
+            obj.insert("accessKey".to_string(), Value::String(masked));
+        }
+        obj.insert("secretKey".to_string(), Value::String(String::new()));
+        obj.remove("sessionToken");
+    }
+    serde_json::to_string(&records).map_err(|e| e.to_string())
+}
+
+// 前端回写脱敏后的配置：Secret Key 为空、Access Key 仍是脱敏值、STS 配置没有 token 时，
+// 沿用同一 id 已保存的凭证
+pub fn restore_oss_secrets(incoming: &str, stored: Option<&str>) -> Result<String, String> {
+    let mut records: Value = serde_json::from_str(incoming).map_err(|e| format!("OSS 配置格式错误: {}", e))?;
+    let stored: Vec<Value> = match stored {
+        Some(text) => serde_json::from_str(text).map_err(|e| format!("OSS 配置格式错误: {}", e))?,
+        None => Vec::new(),
+    };
+    let text_of = |obj: &serde_json::Map<String, Value>, field: &str| obj.get(field).and_then(Value::as_str).unwrap_or_default().to_string();
+
+    for item in records.as_array_mut().into_iter().flatten() {
+        let Some(obj) = item.as_object_mut() else { continue };
+        let id = text_of(obj, "id");
+        let Some(saved) = stored.iter().filter_map(Value::as_object).find(|s| text_of(s, "id") == id) else { continue };
+
+        let saved_access_key = text_of(saved, "accessKey");
+        if text_of(obj, "accessKey") == mask_access_key(&saved_access_key) {
+            obj.insert("accessKey".to_string(), Value::String(saved_access_key));
+        }
+        if text_of(obj, "secretKey").is_empty() {
+            obj.insert("secretKey".to_string(), Value::String(text_of(saved, "secretKey")));
+        }
+        if text_of(obj, "authType") == "sts" && text_of(obj, "sessionToken").is_empty() {
+            if let Some(token) = saved.get("sessionToken") {
+                obj.insert("sessionToken".to_string(), token.clone());
+            }
+        }
+    }
+    serde_json::to_string(&records).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn oss(id: &str) -> Value {
+        json!({
+            "id": id, "name": "n", "provider": "aliyun", "authType": "longterm",
+            "accessKey": "ak", "secretKey": "sk", "region": "", "createdAt": "t", "updatedAt": "t"
+        })
+    }
+
+    #[test]
+    fn encode_then_decode_round_trips() {
+        let text = json!([oss("1")]).to_string();
+        let stored = encode_document("cloud_oss_configs", &text).unwrap();
+        assert!(stored.contains("\"schemaVersion\":2"));
+        let (records, migrated) = decode_document("cloud_oss_configs", &stored).unwrap();
+        assert!(!migrated);
+        assert_eq!(records[0]["accessKey"], "ak");
+    }
+
+    #[test]
+    fn migrates_legacy_array() {
+        let legacy = json!([{
+            "id": "1", "name": "n", "provider": "tencent",
+            "accessKey": "ak", "secretKey": "sk", "sessionToken": "tok"
+        }]);
+        let (records, migrated) = decode_document("cloud_oss_configs", &legacy.to_string()).unwrap();
+        assert!(migrated);
+        assert_eq!(records[0]["authType"], "sts");
+        assert!(records[0]["createdAt"].is_string());
+    }
+
+    #[test]
+    fn rejects_invalid_records() {
+        let mut bad = oss("1");
+        bad["provider"] = json!("unknown-cloud");
+        assert!(encode_document("cloud_oss_configs", &json!([bad]).to_string()).is_err());
+
+        let mut empty_secret = oss("1");
+        empty_secret["secretKey"] = json!("");
+        assert!(encode_document("cloud_oss_configs", &json!([empty_secret]).to_string()).is_err());
+
+        assert!(encode_document("cloud_oss_configs", &json!([oss("1"), oss("1")]).to_string()).is_err());
+        assert!(encode_document("sensitive_feishu", &json!({"id": "1"}).to_string()).is_err());
+    }
+
+    #[test]
+    fn redacts_and_restores_oss_secrets() {
+        let mut saved = oss("1");
+        saved["accessKey"] = json!("LTAI5tExampleKey");
+        saved["authType"] = json!("sts");
+        saved["sessionToken"] = json!("token");
+        let stored = json!([saved]).to_string();
+
+        let redacted: Value = serde_json::from_str(&redact_oss_configs(&stored).unwrap()).unwrap();
+        assert_eq!(redacted[0]["accessKey"], "LTAI****eKey");
+        assert_eq!(redacted[0]["secretKey"], "");
+        assert!(redacted[0].get("sessionToken").is_none());
+
+        // 原样回写时恢复全部凭证；新填写的 Secret Key 保留
+        let mut edited = redacted.clone();
+        edited[0]["name"] = json!("renamed");
+        let restored: Value =
+            serde_json::from_str(&restore_oss_secrets(&edited.to_string(), Some(&stored)).unwrap()).unwrap();
+        assert_eq!(restored[0]["accessKey"], "LTAI5tExampleKey");
+        assert_eq!(restored[0]["secretKey"], "sk");
+        assert_eq!(restored[0]["sessionToken"], "token");
+        assert_eq!(restored[0]["name"], "renamed");
+
+        edited[0]["secretKey"] = json!("new-secret");
+        edited[0]["authType"] = json!("longterm");
+        let restored: Value =
+            serde_json::from_str(&restore_oss_secrets(&edited.to_string(), Some(&stored)).unwrap()).unwrap();
+        assert_eq!(restored[0]["secretKey"], "new-secret");
+        assert!(restored[0].get("sessionToken").is_none());
+    }
+
+    #[test]
+    fn rejects_newer_schema() {
+        let doc = json!({"schemaVersion": CURRENT_SCHEMA_VERSION + 1, "records": []});
+        assert!(decode_document("sensitive_wechat", &doc.to_string()).is_err());
+    }
+
+    #[test]
+    fn untyped_keys_pass_through() {
+        let text = json!([{"anything": 1}]).to_string();
+        let stored = encode_document("recent_operations", &text).unwrap();
+        let (records, _) = decode_document("recent_operations", &stored).unwrap();
+        assert_eq!(records, json!([{"anything": 1}]));
+    }
+}
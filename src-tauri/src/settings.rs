@@ -0,0 +1,91 @@
+use crate::logging::{parse_level, LogState};
+use crate::queue::{TransferManager, MAX_CONCURRENT_TRANSFERS};
+use crate::storage::{atomic_write, data_dir, ensure_data_dir};
+use crate::vault::VaultState;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{command, AppHandle, State};
+
+// 应用设置不含敏感信息，明文保存；不使用 .json 扩展名，避免被当作数据键列出或导出
+const SETTINGS_FILE: &str = "settings.conf";
+// 自动锁定时间上限（分钟），即 24 小时
+const MAX_AUTO_LOCK_MINUTES: u64 = 24 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AppSettings {
+    // 空闲多少分钟后自动锁定保险库，0 表示不自动锁定
+    pub auto_lock_minutes: u64,
+    // 日志级别：off / error / warn / info / debug / trace
+    pub log_level: String,
+    // PermanentRedirect 修正出的 region/endpoint 是否无需确认直接写回配置
+    pub auto_apply_endpoint_correction: bool,
+    // 传输队列同时进行的任务数（1 ~ 8）
+    pub max_concurrent_transfers: usize,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            auto_lock_minutes: 15,
+            log_level: "info".to_string(),
+            auto_apply_endpoint_correction: false,
+            max_concurrent_transfers: 3,
+        }
+    }
+}
+
+impl AppSettings {
+    // 设置文件可能被手工改成极大的值，换算成秒时不能溢出
+    pub(crate) fn idle_timeout_secs(&self) -> u64 {
+        self.auto_lock_minutes.saturating_mul(60)
+    }
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join(SETTINGS_FILE))
+}
+
+// 读取设置，文件不存在或损坏时使用默认值
+pub(crate) fn load_settings() -> AppSettings {
+    settings_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    ensure_data_dir()?;
+    let text = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    atomic_write(&settings_path()?, text.as_bytes())
+}
+
+#[command]
+pub fn get_settings() -> Result<AppSettings, String> {
+    Ok(load_settings())
+}
+
+#[command]
+pub fn update_settings(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    log: State<'_, LogState>,
+    transfers: State<'_, TransferManager>,
+    settings: AppSettings,
+) -> Result<AppSettings, String> {
+    parse_level(&settings.log_level)?;
+    if settings.auto_lock_minutes > MAX_AUTO_LOCK_MINUTES {
+        return Err(format!("自动锁定时间需在 0 ~ {} 分钟之间", MAX_AUTO_LOCK_MINUTES));
+    }
+    if !(1..=MAX_CONCURRENT_TRANSFERS).contains(&settings.max_concurrent_transfers) {
+        return Err(format!("同时传输的任务数需在 1 ~ {} 之间", MAX_CONCURRENT_TRANSFERS));
+    }
+    save_settings(&settings)?;
+    vault.set_idle_timeout(settings.idle_timeout_secs());
+    log.set_level(&settings.log_level)?;
+    transfers.set_max_concurrent(settings.max_concurrent_transfers);
+    transfers.schedule(&app);
+    Ok(settings)
+}
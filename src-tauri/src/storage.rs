@@ -0,0 +1,554 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use tauri::command;
+
+const DATA_DIR_NAME: &str = ".sensitive-info-tool";
+const DATA_FILE_EXT: &str = "json";
+const BACKUP_DIR_NAME: &str = "backups";
+pub(crate) const LOG_DIR_NAME: &str = "logs";
+// 每个键保留的历史版本数
+const MAX_BACKUPS_PER_KEY: usize = 10;
+// 数据目录仅允许当前用户访问
+#[cfg(unix)]
+const DIR_MODE: u32 = 0o700;
+#[cfg(unix)]
+const FILE_MODE: u32 = 0o600;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PermissionIssue {
+    pub path: String,
+    pub is_dir: bool,
+    pub mode: String,
+    pub expected: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DataDirHealth {
+    pub path: String,
+    pub exists: bool,
+    pub healthy: bool,
+    pub issues: Vec<PermissionIssue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DataBackup {
+    pub id: String,
+    pub created_at: String,
+    pub size: u64,
+}
+
+// 数据目录路径（~/.sensitive-info-tool）
+pub(crate) fn data_dir() -> Result<PathBuf, String> {
+    match dirs::home_dir() {
+        Some(home) => Ok(home.join(DATA_DIR_NAME)),
+        None => Err("无法获取用户家目录".to_string()),
+    }
+}
+
+// 创建仅当前用户可访问的目录（Unix 下为 0700）
+pub(crate) fn create_private_dir(dir: &Path) -> Result<(), String> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(DIR_MODE);
+    }
+    builder.create(dir).map_err(|e| format!("创建目录失败: {}", e))
+}
+
+// 将逻辑键解析为数据目录下的文件路径，拒绝任何逃逸出数据目录的键
+pub(crate) fn resolve_key_path(base: &Path, key: &str) -> Result<PathBuf, String> {
+    let invalid = || format!("非法的数据键: {}", key);
+
+    // 键只能是单个普通文件名：不允许绝对路径、分隔符、`.`/`..` 和隐藏文件
+    if key.is_empty() || key.starts_with('.') || key.contains(['/', '\\', '\0']) {
+        return Err(invalid());
+    }
+    let mut components = Path::new(key).components();
+    if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+        return Err(invalid());
+    }
+
+    let base = base
+        .canonicalize()
+        .map_err(|e| format!("解析数据目录失败: {}", e))?;
+    let candidate = base.join(format!("{}.{}", key, DATA_FILE_EXT));
+
+    // 已存在的路径（包括符号链接）必须在规范化后仍位于数据目录内
+    match fs::symlink_metadata(&candidate) {
+        Ok(_) => {
+            let resolved = candidate.canonicalize().map_err(|_| invalid())?;
+            if resolved.parent() != Some(base.as_path()) {
+                return Err(invalid());
+            }
+            Ok(resolved)
+        }
+        Err(_) => Ok(candidate),
+    }
+}
+
+fn key_path(key: &str) -> Result<PathBuf, String> {
+    ensure_data_dir()?;
+    resolve_key_path(&data_dir()?, key)
+}
+
+// 先写同目录下的临时文件并 fsync，再原子 rename 覆盖目标，避免写到一半时崩溃导致文件被截断
+pub(crate) fn atomic_write(path: &Path, data: &[u8]) -> Result<(), String> {
+    let dir = path.parent().ok_or_else(|| "无效的文件路径".to_string())?;
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "无效的文件路径".to_string())?;
+    let tmp_path = dir.join(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4()));
+
+    let result = (|| {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(FILE_MODE);
+        }
+        let mut file = options
+            .open(&tmp_path)
+            .map_err(|e| format!("创建临时文件失败: {}", e))?;
+        file.write_all(data).map_err(|e| format!("写入文件失败: {}", e))?;
+        file.sync_all().map_err(|e| format!("同步文件失败: {}", e))?;
+        fs::rename(&tmp_path, path).map_err(|e| format!("替换文件失败: {}", e))
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return result;
+    }
+
+    // rename 本身也需要落盘，否则掉电后目录项可能仍指向旧文件
+    #[cfg(unix)]
+    if let Ok(d) = fs::File::open(dir) {
+        let _ = d.sync_all();
+    }
+    Ok(())
+}
+
+pub(crate) fn backup_dir(base: &Path, key: &str) -> PathBuf {
+    base.join(BACKUP_DIR_NAME).join(key)
+}
+
+// 把当前版本复制到 backups/<key>/<时间戳>.json，并只保留最近的若干份
+fn backup_current(base: &Path, key: &str, path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let data = fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let dir = backup_dir(base, key);
+    create_private_dir(&dir)?;
+
+    let id = Utc::now().format("%Y%m%dT%H%M%S%.6fZ").to_string();
+    atomic_write(&dir.join(format!("{}.{}", id, DATA_FILE_EXT)), &data)?;
+
+    let backups = list_backups_in(base, key)?;
+    for old in backups.iter().skip(MAX_BACKUPS_PER_KEY) {
+        let _ = fs::remove_file(dir.join(format!("{}.{}", old.id, DATA_FILE_EXT)));
+    }
+    Ok(())
+}
+
+// 按时间倒序列出某个键的历史版本
+fn list_backups_in(base: &Path, key: &str) -> Result<Vec<DataBackup>, String> {
+    let dir = backup_dir(base, key);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut backups = Vec::new();
+    let entries = fs::read_dir(&dir).map_err(|e| format!("读取备份目录失败: {}", e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(DATA_FILE_EXT) {
+            continue;
+        }
+        let (Some(id), Ok(meta)) = (path.file_stem().and_then(|s| s.to_str()), entry.metadata()) else {
+            continue;
+        };
+        let created_at = chrono::NaiveDateTime::parse_from_str(id, "%Y%m%dT%H%M%S%.fZ")
+            .map(|dt| dt.and_utc().to_rfc3339())
+            .unwrap_or_default();
+        backups.push(DataBackup { id: id.to_string(), created_at, size: meta.len() });
+    }
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+// 带备份的写入：先备份旧版本，再原子写入新内容
+fn write_record_in(base: &Path, key: &str, data: &[u8]) -> Result<(), String> {
+    let path = resolve_key_path(base, key)?;
+    backup_current(base, key, &path)?;
+    atomic_write(&path, data)
+}
+
+fn restore_backup_in(base: &Path, key: &str, backup_id: &str) -> Result<(), String> {
+    // 先校验键，避免 ../ 之类的键列举、读取数据目录外的文件
+    resolve_key_path(base, key)?;
+    // 只接受列表中真实存在的备份 id，避免借 id 构造任意路径
+    let backup = list_backups_in(base, key)?
+        .into_iter()
+        .find(|b| b.id == backup_id)
+        .ok_or_else(|| format!("未找到备份: {}", backup_id))?;
+    let data = fs::read(backup_dir(base, key).join(format!("{}.{}", backup.id, DATA_FILE_EXT)))
+        .map_err(|e| format!("读取备份失败: {}", e))?;
+    write_record_in(base, key, &data)
+}
+
+// 先用零覆盖文件内容并落盘，再删除，尽量不在磁盘上留下旧数据
+pub(crate) fn secure_remove(path: &Path) -> Result<(), String> {
+    let len = fs::metadata(path).map_err(|e| format!("读取文件信息失败: {}", e))?.len();
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("打开文件失败: {}", e))?;
+    let zeros = [0u8; 8192];
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(zeros.len() as u64) as usize;
+        file.write_all(&zeros[..n]).map_err(|e| format!("擦除文件失败: {}", e))?;
+        remaining -= n as u64;
+    }
+    file.sync_all().map_err(|e| format!("同步文件失败: {}", e))?;
+    drop(file);
+    fs::remove_file(path).map_err(|e| format!("删除文件失败: {}", e))
+}
+
+// 把数据键当前的文件原样移到数据目录内的隐藏临时位置（不产生备份），返回新路径
+pub(crate) fn move_to_quarantine(key: &str) -> Result<PathBuf, String> {
+    let base = data_dir()?;
+    let path = resolve_key_path(&base, key)?;
+    let target = base.join(format!(".{}.{}.quarantine", key, uuid::Uuid::new_v4()));
+    fs::rename(&path, &target).map_err(|e| format!("移动文件失败: {}", e))?;
+    Ok(target)
+}
+
+pub(crate) fn write_record(key: &str, data: &[u8]) -> Result<(), String> {
+    ensure_data_dir()?;
+    write_record_in(&data_dir()?, key, data)
+}
+
+// 递归检查数据目录及其中文件的权限，组/其他用户有任何权限位即视为异常；
+// 日志文件由滚动写入器按 umask 创建，无法指定权限，只检查 logs 目录本身（0700）
+#[cfg(unix)]
+fn collect_permission_issues(path: &Path, log_dir: &Path, issues: &mut Vec<PermissionIssue>) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Ok(meta) = fs::symlink_metadata(path) else { return };
+    if meta.file_type().is_symlink() {
+        return;
+    }
+    let mode = meta.permissions().mode() & 0o777;
+    let expected = if meta.is_dir() { DIR_MODE } else { FILE_MODE };
+    if mode & 0o077 != 0 {
+        issues.push(PermissionIssue {
+            path: path.to_string_lossy().to_string(),
+            is_dir: meta.is_dir(),
+            mode: format!("{:o}", mode),
+            expected: format!("{:o}", expected),
+        });
+    }
+    if meta.is_dir() && path != log_dir {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                collect_permission_issues(&entry.path(), log_dir, issues);
+            }
+        }
+    }
+}
+
+fn check_health_in(base: &Path) -> DataDirHealth {
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut issues = Vec::new();
+    let exists = base.exists();
+    #[cfg(unix)]
+    if exists {
+        collect_permission_issues(base, &base.join(LOG_DIR_NAME), &mut issues);
+    }
+    DataDirHealth {
+        path: base.to_string_lossy().to_string(),
+        exists,
+        healthy: issues.is_empty(),
+        issues,
+    }
+}
+
+// 启动时检查数据目录权限，发现偏差时输出提示，详情可通过 check_data_dir_health 获取
+pub(crate) fn report_data_dir_health() {
+    let Ok(base) = data_dir() else { return };
+    let health = check_health_in(&base);
+    for issue in &health.issues {
+        tracing::warn!(
+            "数据目录权限异常: {} 当前为 {}，应为 {}",
+            issue.path, issue.mode, issue.expected
+        );
+    }
+}
+
+// 获取用户家目录
+#[command]
+pub fn get_home_dir() -> Result<String, String> {
+    match dirs::home_dir() {
+        Some(path) => Ok(path.to_string_lossy().to_string()),
+        None => Err("无法获取用户家目录".to_string()),
+    }
+}
+
+// 获取数据目录
+#[command]
+pub fn get_data_dir() -> Result<String, String> {
+    Ok(data_dir()?.to_string_lossy().to_string())
+}
+
+// 确保数据目录存在
+#[command]
+pub fn ensure_data_dir() -> Result<(), String> {
+    let data_dir = data_dir()?;
+    if !data_dir.exists() {
+        create_private_dir(&data_dir)
+            .map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+    Ok(())
+}
+
+// 写入数据文件
+#[command]
+pub fn write_data_file(key: String, data: String) -> Result<(), String> {
+    write_record(&key, data.as_bytes())
+}
+
+// 读取数据文件
+#[command]
+pub fn read_data_file(key: String) -> Result<String, String> {
+    let path = key_path(&key)?;
+    fs::read_to_string(&path)
+        .map_err(|e| format!("读取文件失败: {}", e))
+}
+
+// 删除数据文件
+#[command]
+pub fn delete_data_file(key: String) -> Result<(), String> {
+    let path = key_path(&key)?;
+    // 删除前保留一份备份，误删后仍可恢复
+    backup_current(&data_dir()?, &key, &path)?;
+    fs::remove_file(&path)
+        .map_err(|e| format!("删除文件失败: {}", e))?;
+    Ok(())
+}
+
+// 列出数据文件
+#[command]
+pub fn list_data_files() -> Result<Vec<String>, String> {
+    let data_dir = data_dir()?;
+    if !data_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut files = Vec::new();
+    if let Ok(entries) = fs::read_dir(&data_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(DATA_FILE_EXT) {
+                continue;
+            }
+            if let Some(key) = path.file_stem().and_then(|s| s.to_str()) {
+                // 只列出能通过键校验的文件，与读写命令保持一致
+                if resolve_key_path(&data_dir, key).is_ok() {
+                    files.push(key.to_string());
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+// 列出某个数据键的历史版本
+#[command]
+pub fn list_data_backups(key: String) -> Result<Vec<DataBackup>, String> {
+    let base = data_dir()?;
+    resolve_key_path(&base, &key)?;
+    list_backups_in(&base, &key)
+}
+
+// 将数据键恢复到指定的历史版本（恢复前的当前版本同样会被备份）
+#[command]
+pub fn restore_data_backup(key: String, backup_id: String) -> Result<(), String> {
+    ensure_data_dir()?;
+    restore_backup_in(&data_dir()?, &key, &backup_id)
+}
+
+// 检查数据目录及文件权限
+#[command]
+pub fn check_data_dir_health() -> Result<DataDirHealth, String> {
+    Ok(check_health_in(&data_dir()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> tempfile::TempDir {
+        tempfile::tempdir().expect("create temp dir")
+    }
+
+    #[test]
+    fn resolves_plain_key_inside_base() {
+        let dir = base();
+        let path = resolve_key_path(dir.path(), "cloud_oss_configs").unwrap();
+        assert_eq!(path, dir.path().canonicalize().unwrap().join("cloud_oss_configs.json"));
+    }
+
+    #[test]
+    fn rejects_parent_dir_traversal() {
+        let dir = base();
+        for key in ["..", "../secret", "a/../../b", "..\\b", "sub/key", "."] {
+            assert!(resolve_key_path(dir.path(), key).is_err(), "key {:?} should be rejected", key);
+        }
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        let dir = base();
+        for key in ["/etc/passwd", "\\Windows\\win.ini", "C:\\Windows\\win.ini"] {
+            assert!(resolve_key_path(dir.path(), key).is_err(), "key {:?} should be rejected", key);
+        }
+    }
+
+    #[test]
+    fn rejects_empty_and_hidden_keys() {
+        let dir = base();
+        assert!(resolve_key_path(dir.path(), "").is_err());
+        assert!(resolve_key_path(dir.path(), ".hidden").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlink_escaping_base() {
+        let dir = base();
+        let outside = base();
+        let target = outside.path().join("victim.json");
+        fs::write(&target, "{}").unwrap();
+        std::os::unix::fs::symlink(&target, dir.path().join("evil.json")).unwrap();
+        assert!(resolve_key_path(dir.path(), "evil").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_dangling_symlink() {
+        let dir = base();
+        let outside = base();
+        std::os::unix::fs::symlink(outside.path().join("missing.json"), dir.path().join("dangling.json")).unwrap();
+        assert!(resolve_key_path(dir.path(), "dangling").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn allows_symlink_within_base() {
+        let dir = base();
+        fs::write(dir.path().join("real.json"), "{}").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("real.json"), dir.path().join("alias.json")).unwrap();
+        let path = resolve_key_path(dir.path(), "alias").unwrap();
+        assert_eq!(path, dir.path().canonicalize().unwrap().join("real.json"));
+    }
+
+    #[test]
+    fn atomic_write_replaces_content_without_leftovers() {
+        let dir = base();
+        let path = dir.path().join("k.json");
+        atomic_write(&path, b"first").unwrap();
+        atomic_write(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn keeps_limited_number_of_backups() {
+        let dir = base();
+        for i in 0..(MAX_BACKUPS_PER_KEY + 3) {
+            write_record_in(dir.path(), "k", format!("v{}", i).as_bytes()).unwrap();
+        }
+        let backups = list_backups_in(dir.path(), "k").unwrap();
+        assert_eq!(backups.len(), MAX_BACKUPS_PER_KEY);
+        // 最新的备份是最后一次写入前的版本
+        let newest = fs::read(backup_dir(dir.path(), "k").join(format!("{}.json", backups[0].id))).unwrap();
+        assert_eq!(newest, format!("v{}", MAX_BACKUPS_PER_KEY + 1).as_bytes());
+    }
+
+    #[test]
+    fn restores_earlier_version() {
+        let dir = base();
+        write_record_in(dir.path(), "k", b"old").unwrap();
+        write_record_in(dir.path(), "k", b"new").unwrap();
+        let backups = list_backups_in(dir.path(), "k").unwrap();
+        restore_backup_in(dir.path(), "k", &backups[0].id).unwrap();
+        assert_eq!(fs::read(dir.path().join("k.json")).unwrap(), b"old");
+        assert!(restore_backup_in(dir.path(), "k", "../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn restore_rejects_key_traversal() {
+        let dir = base();
+        let data = dir.path().join("data");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&data).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("20240101T000000.000000Z.json"), b"secret").unwrap();
+        let error = restore_backup_in(&data, "../../outside", "20240101T000000.000000Z").unwrap_err();
+        assert!(error.contains("非法的数据键"), "{}", error);
+    }
+
+    #[test]
+    fn secure_remove_deletes_file() {
+        let dir = base();
+        let path = dir.path().join("old.json");
+        fs::write(&path, "legacy secret").unwrap();
+        secure_remove(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_private_files_and_reports_drift() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = base();
+        fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o700)).unwrap();
+        write_record_in(dir.path(), "k", b"v1").unwrap();
+        write_record_in(dir.path(), "k", b"v2").unwrap();
+        let path = dir.path().join("k.json");
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(check_health_in(dir.path()).healthy);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        let health = check_health_in(dir.path());
+        assert!(!health.healthy);
+        assert_eq!(health.issues.len(), 1);
+        assert_eq!(health.issues[0].mode, "644");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn checks_log_dir_but_not_log_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = base();
+        fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o700)).unwrap();
+        let logs = dir.path().join(LOG_DIR_NAME);
+        create_private_dir(&logs).unwrap();
+        let log = logs.join("app.2024-01-01.log");
+        fs::write(&log, b"line").unwrap();
+        fs::set_permissions(&log, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(check_health_in(dir.path()).healthy);
+
+        fs::set_permissions(&logs, fs::Permissions::from_mode(0o755)).unwrap();
+        let health = check_health_in(dir.path());
+        assert_eq!(health.issues.len(), 1);
+        assert!(health.issues[0].is_dir);
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+
+const SIGNAL_RUN: u8 = 0;
+const SIGNAL_PAUSE: u8 = 1;
+const SIGNAL_CANCEL: u8 = 2;
+
+// 传输的停止信号：暂停保留断点，取消会清理断点
+#[derive(Debug, Clone, Default)]
+pub struct TransferSignal(Arc<AtomicU8>);
+
+impl TransferSignal {
+    pub(crate) fn pause(&self) {
+        let _ = self.0.compare_exchange(SIGNAL_RUN, SIGNAL_PAUSE, Ordering::SeqCst, Ordering::SeqCst);
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.0.store(SIGNAL_CANCEL, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst) == SIGNAL_PAUSE
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst) == SIGNAL_CANCEL
+    }
+
+    pub(crate) fn should_stop(&self) -> bool {
+        self.0.load(Ordering::SeqCst) != SIGNAL_RUN
+    }
+}
+
+// 运行中的上传/下载，按传输 id（分片上传为 upload_id，下载为本地路径）登记停止信号
+#[derive(Default)]
+pub struct ActiveTransfers(Mutex<HashMap<String, TransferSignal>>);
+
+impl ActiveTransfers {
+    pub(crate) fn register(&self, id: &str, signal: &TransferSignal) -> ActiveTransferGuard<'_> {
+        if let Ok(mut map) = self.0.lock() {
+            map.insert(id.to_string(), signal.clone());
+        }
+        ActiveTransferGuard { active: self, id: id.to_string() }
+    }
+
+    // 通知正在进行的传输取消，返回该传输是否正在进行
+    pub fn cancel(&self, id: &str) -> bool {
+        let signal = self.0.lock().ok().and_then(|map| map.get(id).cloned());
+        match signal {
+            Some(signal) => {
+                signal.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// 传输结束（成功、失败、暂停或取消）时自动注销
+pub(crate) struct ActiveTransferGuard<'a> {
+    active: &'a ActiveTransfers,
+    id: String,
+}
+
+impl Drop for ActiveTransferGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut map) = self.active.0.lock() {
+            map.remove(&self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pause_does_not_override_cancel() {
+        let signal = TransferSignal::default();
+        signal.pause();
+        assert!(signal.is_paused() && signal.should_stop());
+        signal.cancel();
+        signal.pause();
+        assert!(signal.is_cancelled());
+    }
+
+    #[test]
+    fn cancel_and_unregister() {
+        let active = ActiveTransfers::default();
+        let signal = TransferSignal::default();
+        {
+            let _guard = active.register("u1", &signal);
+            assert!(active.cancel("u1"));
+            assert!(signal.is_cancelled());
+        }
+        assert!(!active.cancel("u1"));
+    }
+}
@@ -0,0 +1,451 @@
+use crate::oss::{
+    execute_on_bucket, load_oss_config, remember_correction, upload_key, OssConfig, OssResult, RetryPolicy,
+};
+use crate::storage::{atomic_write, create_private_dir, data_dir};
+use crate::transfer::{ActiveTransfers, TransferSignal};
+use crate::vault::VaultState;
+use chrono::Utc;
+use futures_util::stream::{self, TryStreamExt};
+use s3::serde_types::Part;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use tauri::{command, State, Window};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+// 分片上传断点记录目录（位于数据目录下，不含任何凭证）
+const UPLOAD_DIR_NAME: &str = "uploads";
+const UPLOAD_PROGRESS_EVENT: &str = "oss-upload-progress";
+
+const MIB: u64 = 1024 * 1024;
+const DEFAULT_PART_SIZE_MB: u64 = 8;
+// S3 协议限制：除最后一片外每片 5MB ~ 5GB，最多 10000 片
+const MIN_PART_SIZE: u64 = 5 * MIB;
+const MAX_PART_SIZE: u64 = 5 * 1024 * MIB;
+const MAX_PARTS: u64 = 10_000;
+const DEFAULT_CONCURRENCY: usize = 4;
+const MAX_CONCURRENCY: usize = 16;
+
+const PART_POLICY: RetryPolicy = RetryPolicy::new(120, "分片上传超时，请检查网络或 Endpoint 配置");
+const MULTIPART_POLICY: RetryPolicy = RetryPolicy::new(60, "请求超时，请检查网络或 Endpoint 配置");
+
+const UPLOAD_CANCELLED: &str = "上传已取消";
+const UPLOAD_PAUSED: &str = "上传已暂停";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OssUploadPathParams {
+    pub local_path: String,
+    // 目标目录，与 oss_upload_file 的 path 含义一致
+    pub path: Option<String>,
+    // 不指定时使用本地文件名
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub part_size_mb: Option<u64>,
+    pub concurrency: Option<usize>,
+    // 续传时传入中断前的 upload_id
+    pub upload_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadProgress {
+    pub upload_id: String,
+    pub key: String,
+    pub uploaded_bytes: u64,
+    pub total_bytes: u64,
+    pub parts_done: u32,
+    pub parts_total: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadedPart {
+    pub part_number: u32,
+    pub etag: String,
+    pub size: u64,
+}
+
+// 分片上传断点：已完成的分片及其 ETag，用于中断后续传
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadCheckpoint {
+    pub upload_id: String,
+    pub bucket: String,
+    pub key: String,
+    pub local_path: String,
+    pub file_size: u64,
+    // 本地文件修改时间（秒），续传前确认文件未被改动
+    pub modified: u64,
+    pub part_size: u64,
+    pub content_type: String,
+    pub parts: Vec<UploadedPart>,
+    pub created_at: String,
+}
+
+impl UploadCheckpoint {
+    fn parts_total(&self) -> u32 {
+        self.file_size.div_ceil(self.part_size) as u32
+    }
+
+    fn progress(&self) -> UploadProgress {
+        UploadProgress {
+            upload_id: self.upload_id.clone(),
+            key: self.key.clone(),
+            uploaded_bytes: self.parts.iter().map(|p| p.size).sum(),
+            total_bytes: self.file_size,
+            parts_done: self.parts.len() as u32,
+            parts_total: self.parts_total(),
+        }
+    }
+}
+
+fn upload_dir() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join(UPLOAD_DIR_NAME))
+}
+
+// upload_id 由服务端生成，字符集不可控，用哈希作为文件名
+fn checkpoint_path(upload_id: &str) -> Result<PathBuf, String> {
+    let name = hex::encode(Sha256::digest(upload_id.as_bytes()));
+    Ok(upload_dir()?.join(format!("{}.json", name)))
+}
+
+fn save_checkpoint(checkpoint: &UploadCheckpoint) -> Result<(), String> {
+    create_private_dir(&upload_dir()?)?;
+    let text = serde_json::to_string_pretty(checkpoint).map_err(|e| e.to_string())?;
+    atomic_write(&checkpoint_path(&checkpoint.upload_id)?, text.as_bytes())
+}
+
+fn load_checkpoint(upload_id: &str) -> Result<UploadCheckpoint, String> {
+    let text = fs::read_to_string(checkpoint_path(upload_id)?).map_err(|_| format!("未找到上传记录: {}", upload_id))?;
+    serde_json::from_str(&text).map_err(|e| format!("上传记录格式错误: {}", e))
+}
+
+fn remove_checkpoint(upload_id: &str) {
+    if let Ok(path) = checkpoint_path(upload_id) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn modified_secs(meta: &fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// 分片大小：按用户设置限制在协议范围内，文件过大时自动放大以不超过 10000 片
+fn choose_part_size(file_size: u64, part_size_mb: Option<u64>) -> u64 {
+    let requested = part_size_mb
+        .unwrap_or(DEFAULT_PART_SIZE_MB)
+        .saturating_mul(MIB)
+        .clamp(MIN_PART_SIZE, MAX_PART_SIZE);
+    requested.max(file_size.div_ceil(MAX_PARTS))
+}
+
+async fn read_chunk(path: &Path, offset: u64, len: u64) -> Result<Vec<u8>, String> {
+    let mut file = tokio::fs::File::open(path).await.map_err(|e| format!("读取本地文件失败: {}", e))?;
+    file.seek(SeekFrom::Start(offset)).await.map_err(|e| format!("读取本地文件失败: {}", e))?;
+    let mut buf = vec![0u8; len as usize];
+    file.read_exact(&mut buf).await.map_err(|e| format!("读取本地文件失败: {}", e))?;
+    Ok(buf)
+}
+
+// 续传时校验断点与本次参数一致，且本地文件未变化
+// 分片上传的 Content-Type 在发起时已确定，续传时显式指定了不同的类型则拒绝
+fn resume_checkpoint(
+    upload_id: &str,
+    bucket: &str,
+    key: &str,
+    local_path: &str,
+    content_type: Option<&str>,
+    meta: &fs::Metadata,
+) -> Result<UploadCheckpoint, String> {
+    let checkpoint = load_checkpoint(upload_id)?;
+    if checkpoint.bucket != bucket || checkpoint.key != key || checkpoint.local_path != local_path {
+        return Err("续传参数与上传记录不一致".to_string());
+    }
+    if content_type.is_some_and(|t| t != checkpoint.content_type) {
+        return Err(format!("续传的 Content-Type 与上传记录不一致（记录为 {}）", checkpoint.content_type));
+    }
+    if checkpoint.file_size != meta.len() || checkpoint.modified != modified_secs(meta) {
+        return Err("本地文件已变化，无法续传，请重新上传".to_string());
+    }
+    Ok(checkpoint)
+}
+
+// 从本地路径分片上传；on_progress 在每个分片完成后调用。
+// signal 被暂停时保留断点，被取消时中止服务端的分片上传
+pub(crate) async fn upload_path(
+    cfg: &OssConfig,
+    params: &OssUploadPathParams,
+    active: &ActiveTransfers,
+    signal: &TransferSignal,
+    on_progress: impl Fn(&UploadProgress) + Send + Sync,
+) -> Result<OssResult<String>, String> {
+    let local_path = PathBuf::from(&params.local_path);
+    let meta = fs::metadata(&local_path).map_err(|e| format!("读取本地文件失败: {}", e))?;
+    if !meta.is_file() {
+        return Err(format!("不是文件: {}", params.local_path));
+    }
+    let file_name = params
+        .file_name
+        .clone()
+        .or_else(|| local_path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .ok_or_else(|| "无法确定文件名".to_string())?;
+    let key = upload_key(params.path.as_deref(), &file_name);
+    let content_type = params.content_type.clone().unwrap_or_else(|| {
+        mime_guess::from_path(&file_name).first_or_octet_stream().to_string()
+    });
+    let bucket_name = cfg.bucket.clone().ok_or_else(|| "Missing bucket name".to_string())?;
+    let part_size = choose_part_size(meta.len(), params.part_size_mb);
+
+    let mut cfg = cfg.clone();
+    let mut correction = None;
+
+    // 小文件直接单次上传
+    if params.upload_id.is_none() && meta.len() <= part_size {
+        let data = read_chunk(&local_path, 0, meta.len()).await?;
+        let (key_ref, data, content_type) = (&key, &data, &content_type);
+        let outcome = execute_on_bucket(&cfg, PART_POLICY, |bucket| async move {
+            bucket.put_object_with_content_type(key_ref, data, content_type).await
+        })
+        .await?;
+        on_progress(&UploadProgress {
+            upload_id: String::new(),
+            key: key.clone(),
+            uploaded_bytes: meta.len(),
+            total_bytes: meta.len(),
+            parts_done: 1,
+            parts_total: 1,
+        });
+        return Ok(OssResult {
+            success: true,
+            data: Some(key),
+            message: Some("文件上传成功".to_string()),
+            error: None,
+            correction: outcome.correction,
+        });
+    }
+
+    let checkpoint = match &params.upload_id {
+        Some(upload_id) => {
+            resume_checkpoint(upload_id, &bucket_name, &key, &params.local_path, params.content_type.as_deref(), &meta)?
+        }
+        None => {
+            let (key_ref, content_type_ref) = (&key, &content_type);
+            let outcome = execute_on_bucket(&cfg, MULTIPART_POLICY, |bucket| async move {
+                bucket.initiate_multipart_upload(key_ref, content_type_ref).await
+            })
+            .await?;
+            if let Some(c) = &outcome.correction {
+                c.apply(&mut cfg);
+            }
+            correction = outcome.correction;
+            let checkpoint = UploadCheckpoint {
+                upload_id: outcome.value.upload_id,
+                bucket: bucket_name,
+                key: key.clone(),
+                local_path: params.local_path.clone(),
+                file_size: meta.len(),
+                modified: modified_secs(&meta),
+                part_size,
+                content_type: content_type.clone(),
+                parts: Vec::new(),
+                created_at: Utc::now().to_rfc3339(),
+            };
+            save_checkpoint(&checkpoint)?;
+            checkpoint
+        }
+    };
+
+    let upload_id = checkpoint.upload_id.clone();
+    let content_type = checkpoint.content_type.clone();
+    let part_size = checkpoint.part_size;
+    let file_size = checkpoint.file_size;
+    let pending: Vec<u32> = (1..=checkpoint.parts_total())
+        .filter(|n| !checkpoint.parts.iter().any(|p| p.part_number == *n))
+        .collect();
+    on_progress(&checkpoint.progress());
+
+    let _guard = active.register(&upload_id, signal);
+    let shared = Mutex::new(checkpoint);
+    let concurrency = params.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+
+    let result = stream::iter(pending.into_iter().map(Ok))
+        .try_for_each_concurrent(concurrency, |part_number| {
+            let (cfg, key, upload_id, content_type) = (&cfg, &key, &upload_id, &content_type);
+            let (local_path, shared, on_progress) = (&local_path, &shared, &on_progress);
+            async move {
+                if signal.should_stop() {
+                    return Err(UPLOAD_PAUSED.to_string());
+                }
+                let offset = (part_number as u64 - 1) * part_size;
+                let len = part_size.min(file_size - offset);
+                let chunk = read_chunk(local_path, offset, len).await?;
+                let outcome = execute_on_bucket(cfg, PART_POLICY, |bucket| {
+                    let chunk = chunk.clone();
+                    async move { bucket.put_multipart_chunk(chunk, key, part_number, upload_id, content_type).await }
+                })
+                .await?;
+
+                let progress = {
+                    let mut checkpoint = shared.lock().map_err(|_| "上传状态异常".to_string())?;
+                    checkpoint.parts.push(UploadedPart { part_number, etag: outcome.value.etag, size: len });
+                    save_checkpoint(&checkpoint)?;
+                    checkpoint.progress()
+                };
+                on_progress(&progress);
+                Ok(())
+            }
+        })
+        .await;
+
+    if signal.is_paused() {
+        return Err(UPLOAD_PAUSED.to_string());
+    }
+    if signal.is_cancelled() {
+        let (key_ref, id_ref) = (&key, &upload_id);
+        let _ = execute_on_bucket(&cfg, MULTIPART_POLICY, |bucket| async move { bucket.abort_upload(key_ref, id_ref).await }).await;
+        remove_checkpoint(&upload_id);
+        return Err(UPLOAD_CANCELLED.to_string());
+    }
+    if let Err(e) = result {
+        // 保留断点，之后可凭 upload_id 续传
+        return Err(format!("{}（可使用 upload_id 续传）", e));
+    }
+
+    let mut parts: Vec<Part> = shared
+        .lock()
+        .map_err(|_| "上传状态异常".to_string())?
+        .parts
+        .iter()
+        .map(|p| Part { part_number: p.part_number, etag: p.etag.clone() })
+        .collect();
+    parts.sort_by_key(|p| p.part_number);
+    let (key_ref, id_ref, parts_ref) = (&key, &upload_id, &parts);
+    execute_on_bucket(&cfg, MULTIPART_POLICY, |bucket| async move {
+        bucket.complete_multipart_upload(key_ref, id_ref, parts_ref.clone()).await
+    })
+    .await?;
+    remove_checkpoint(&upload_id);
+
+    Ok(OssResult {
+        success: true,
+        data: Some(key),
+        message: Some("文件上传成功".to_string()),
+        error: None,
+        correction,
+    })
+}
+
+pub(crate) async fn abort_upload(cfg: &OssConfig, active: &ActiveTransfers, upload_id: &str) -> Result<OssResult<String>, String> {
+    // 正在上传时由上传任务自行中止并清理断点
+    if !active.cancel(upload_id) {
+        let checkpoint = load_checkpoint(upload_id)?;
+        let (key, id) = (&checkpoint.key, &checkpoint.upload_id);
+        execute_on_bucket(cfg, MULTIPART_POLICY, |bucket| async move { bucket.abort_upload(key, id).await }).await?;
+        remove_checkpoint(upload_id);
+    }
+    Ok(OssResult {
+        success: true,
+        data: Some(upload_id.to_string()),
+        message: Some("上传已取消".to_string()),
+        error: None,
+        correction: None,
+    })
+}
+
+#[command]
+pub async fn oss_upload_path(
+    window: Window,
+    active: State<'_, ActiveTransfers>,
+    cfg: OssConfig,
+    params: OssUploadPathParams,
+) -> Result<OssResult<String>, String> {
+    upload_path(&cfg, &params, &active, &TransferSignal::default(), |progress| {
+        let _ = window.emit(UPLOAD_PROGRESS_EVENT, progress.clone());
+    })
+    .await
+}
+
+#[command]
+pub async fn oss_upload_path_by_id(
+    window: Window,
+    vault: State<'_, VaultState>,
+    active: State<'_, ActiveTransfers>,
+    config_id: String,
+    params: OssUploadPathParams,
+) -> Result<OssResult<String>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    let mut result = upload_path(&cfg, &params, &active, &TransferSignal::default(), |progress| {
+        let _ = window.emit(UPLOAD_PROGRESS_EVENT, progress.clone());
+    })
+    .await?;
+    remember_correction(&vault, &config_id, &mut result);
+    Ok(result)
+}
+
+#[command]
+pub async fn oss_abort_upload(active: State<'_, ActiveTransfers>, cfg: OssConfig, upload_id: String) -> Result<OssResult<String>, String> {
+    abort_upload(&cfg, &active, &upload_id).await
+}
+
+#[command]
+pub async fn oss_abort_upload_by_id(
+    vault: State<'_, VaultState>,
+    active: State<'_, ActiveTransfers>,
+    config_id: String,
+    upload_id: String,
+) -> Result<OssResult<String>, String> {
+    let cfg = load_oss_config(&vault, &config_id)?;
+    abort_upload(&cfg, &active, &upload_id).await
+}
+
+// 列出可续传的分片上传
+#[command]
+pub fn oss_list_resumable_uploads() -> Result<Vec<UploadCheckpoint>, String> {
+    let Ok(entries) = fs::read_dir(upload_dir()?) else { return Ok(vec![]) };
+    let mut uploads: Vec<UploadCheckpoint> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|text| serde_json::from_str(&text).ok())
+        .collect();
+    uploads.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(uploads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_size_respects_protocol_limits() {
+        assert_eq!(choose_part_size(100 * MIB, None), DEFAULT_PART_SIZE_MB * MIB);
+        assert_eq!(choose_part_size(100 * MIB, Some(1)), MIN_PART_SIZE);
+        assert_eq!(choose_part_size(100 * MIB, Some(u64::MAX)), MAX_PART_SIZE);
+        // 100GB 按 8MB 分片会超过 10000 片，需要放大
+        let size = 100 * 1024 * MIB;
+        assert!(size.div_ceil(choose_part_size(size, None)) <= MAX_PARTS);
+    }
+
+    #[test]
+    fn checkpoint_progress_counts_completed_parts() {
+        let checkpoint = UploadCheckpoint {
+            upload_id: "id".to_string(),
+            bucket: "b".to_string(),
+            key: "k".to_string(),
+            local_path: "/tmp/f".to_string(),
+            file_size: 12 * MIB,
+            modified: 0,
+            part_size: 5 * MIB,
+            content_type: "application/octet-stream".to_string(),
+            parts: vec![UploadedPart { part_number: 3, etag: "e".to_string(), size: 2 * MIB }],
+            created_at: String::new(),
+        };
+        let progress = checkpoint.progress();
+        assert_eq!((progress.parts_done, progress.parts_total), (1, 3));
+        assert_eq!(progress.uploaded_bytes, 2 * MIB);
+    }
+}
@@ -0,0 +1,327 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{command, State};
+use crate::records::{decode_document, encode_document, redact_oss_configs, restore_oss_secrets, CLOUD_OSS_CONFIGS_KEY};
+use crate::storage::{atomic_write, data_dir, ensure_data_dir, resolve_key_path, write_record};
+use zeroize::Zeroizing;
+
+// 主密码校验用的已知明文，解锁时能解开即说明密码正确
+const VAULT_CHECK_PLAINTEXT: &[u8] = b"sensitive-info-tool-vault";
+const VAULT_META_FILE: &str = "vault.meta";
+const ENVELOPE_VERSION: u32 = 1;
+const ENVELOPE_ALG: &str = "xchacha20poly1305";
+
+// Argon2id 参数（OWASP 推荐的最低配置：19 MiB / 2 轮 / 1 并行度）
+const KDF_M_COST: u32 = 19 * 1024;
+const KDF_T_COST: u32 = 2;
+const KDF_P_COST: u32 = 1;
+// 外部导入包携带的 KDF 参数上限，超出时拒绝派生，避免构造的参数耗尽内存或 CPU
+const MAX_KDF_M_COST: u32 = 256 * 1024;
+const MAX_KDF_T_COST: u32 = 10;
+const MAX_KDF_P_COST: u32 = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KdfParams {
+    algorithm: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+}
+
+impl KdfParams {
+    // 生成带随机盐值的默认 Argon2id 参数
+    pub fn generate() -> Self {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        KdfParams {
+            algorithm: "argon2id".to_string(),
+            m_cost: KDF_M_COST,
+            t_cost: KDF_T_COST,
+            p_cost: KDF_P_COST,
+            salt: B64.encode(salt),
+        }
+    }
+
+    // 校验来自外部文件的参数不超过上限
+    pub fn check_limits(&self) -> Result<(), String> {
+        if self.m_cost > MAX_KDF_M_COST || self.t_cost > MAX_KDF_T_COST || self.p_cost > MAX_KDF_P_COST {
+            return Err(format!(
+                "KDF 参数超出允许范围（内存 ≤ {} KiB，迭代 ≤ {}，并行度 ≤ {}）",
+                MAX_KDF_M_COST, MAX_KDF_T_COST, MAX_KDF_P_COST
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct VaultMeta {
+    version: u32,
+    kdf: KdfParams,
+    check: Envelope,
+}
+
+// 单条记录的加密信封，落盘时即为 <key>.json 的内容
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Envelope {
+    pub v: u32,
+    pub alg: String,
+    pub nonce: String,
+    pub ct: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VaultStatus {
+    pub initialized: bool,
+    pub unlocked: bool,
+}
+
+struct VaultSession {
+    key: Zeroizing<[u8; 32]>,
+    last_used: Instant,
+}
+
+pub struct VaultState {
+    session: Mutex<Option<VaultSession>>,
+    // 空闲超时（秒），0 表示不自动锁定
+    idle_timeout_secs: AtomicU64,
+}
+
+fn meta_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join(VAULT_META_FILE))
+}
+
+fn record_path(key: &str) -> Result<PathBuf, String> {
+    ensure_data_dir()?;
+    resolve_key_path(&data_dir()?, key)
+}
+
+pub fn derive_key(password: &str, kdf: &KdfParams) -> Result<Zeroizing<[u8; 32]>, String> {
+    if kdf.algorithm != "argon2id" {
+        return Err(format!("不支持的密钥派生算法: {}", kdf.algorithm));
+    }
+    let salt = B64.decode(&kdf.salt).map_err(|e| format!("盐值格式错误: {}", e))?;
+    let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32))
+        .map_err(|e| format!("KDF 参数错误: {}", e))?;
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    argon2
+        .hash_password_into(password.as_bytes(), &salt, key.as_mut())
+        .map_err(|e| format!("派生密钥失败: {}", e))?;
+    Ok(key)
+}
+
+// 以记录键作为附加数据，防止把一条记录的密文替换到另一个键下
+pub fn seal(key: &[u8; 32], aad: &str, plaintext: &[u8]) -> Result<Envelope, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let mut nonce = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ct = cipher
+        .encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext, aad: aad.as_bytes() })
+        .map_err(|_| "加密失败".to_string())?;
+    Ok(Envelope {
+        v: ENVELOPE_VERSION,
+        alg: ENVELOPE_ALG.to_string(),
+        nonce: B64.encode(nonce),
+        ct: B64.encode(ct),
+    })
+}
+
+pub fn open(key: &[u8; 32], aad: &str, envelope: &Envelope) -> Result<Zeroizing<Vec<u8>>, String> {
+    if envelope.v != ENVELOPE_VERSION || envelope.alg != ENVELOPE_ALG {
+        return Err(format!("不支持的加密格式: v{} {}", envelope.v, envelope.alg));
+    }
+    let nonce = B64.decode(&envelope.nonce).map_err(|e| format!("nonce 格式错误: {}", e))?;
+    if nonce.len() != 24 {
+        return Err("nonce 长度错误".to_string());
+    }
+    let ct = B64.decode(&envelope.ct).map_err(|e| format!("密文格式错误: {}", e))?;
+    let cipher = XChaCha20Poly1305::new(key.into());
+    cipher
+        .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ct, aad: aad.as_bytes() })
+        .map(Zeroizing::new)
+        .map_err(|_| "解密失败，密码错误或数据已损坏".to_string())
+}
+
+fn read_meta() -> Result<Option<VaultMeta>, String> {
+    let path = meta_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("读取保险库元数据失败: {}", e))?;
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| format!("保险库元数据格式错误: {}", e))
+}
+
+// 首次解锁时用主密码初始化保险库
+fn init_meta(password: &str) -> Result<(VaultMeta, Zeroizing<[u8; 32]>), String> {
+    let kdf = KdfParams::generate();
+    let key = derive_key(password, &kdf)?;
+    let check = seal(&key, VAULT_META_FILE, VAULT_CHECK_PLAINTEXT)?;
+    let meta = VaultMeta { version: 1, kdf, check };
+
+    ensure_data_dir()?;
+    let text = serde_json::to_string_pretty(&meta).map_err(|e| e.to_string())?;
+    atomic_write(&meta_path()?, text.as_bytes())?;
+    Ok((meta, key))
+}
+
+// 导出等敏感操作前再次校验主密码，不依赖当前是否已解锁
+pub(crate) fn verify_master_password(password: &str) -> Result<(), String> {
+    let meta = read_meta()?.ok_or_else(|| "保险库尚未初始化".to_string())?;
+    let key = derive_key(password, &meta.kdf)?;
+    open(&key, VAULT_META_FILE, &meta.check).map_err(|_| "主密码错误".to_string())?;
+    Ok(())
+}
+
+impl VaultState {
+    pub fn new(idle_timeout_secs: u64) -> Self {
+        VaultState {
+            session: Mutex::new(None),
+            idle_timeout_secs: AtomicU64::new(idle_timeout_secs),
+        }
+    }
+
+    pub fn set_idle_timeout(&self, secs: u64) {
+        self.idle_timeout_secs.store(secs, Ordering::Relaxed);
+    }
+
+    fn is_idle(&self, session: &VaultSession) -> bool {
+        let timeout = self.idle_timeout_secs.load(Ordering::Relaxed);
+        timeout > 0 && session.last_used.elapsed() >= Duration::from_secs(timeout)
+    }
+
+    // 在已解锁的会话密钥上执行操作，未解锁或已超时时返回错误；每次使用都会刷新空闲计时
+    pub fn with_key<T>(&self, f: impl FnOnce(&[u8; 32]) -> Result<T, String>) -> Result<T, String> {
+        let mut guard = self.session.lock().map_err(|_| "保险库状态异常".to_string())?;
+        if guard.as_ref().map(|s| self.is_idle(s)).unwrap_or(false) {
+            *guard = None;
+        }
+        match guard.as_mut() {
+            Some(session) => {
+                session.last_used = Instant::now();
+                f(&session.key)
+            }
+            None => Err("保险库已锁定，请先输入主密码解锁".to_string()),
+        }
+    }
+
+    // 空闲超时则丢弃会话密钥，返回是否因此发生了锁定
+    pub fn lock_if_idle(&self) -> bool {
+        let Ok(mut guard) = self.session.lock() else { return false };
+        if guard.as_ref().map(|s| self.is_idle(s)).unwrap_or(false) {
+            *guard = None;
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn is_unlocked(&self) -> bool {
+        self.session
+            .lock()
+            .map(|s| s.as_ref().map(|s| !self.is_idle(s)).unwrap_or(false))
+            .unwrap_or(false)
+    }
+
+    // 读取并解密一条记录，旧版本结构会在读取时升级并写回，文件不存在时返回 None
+    pub fn load_record(&self, key: &str) -> Result<Option<Zeroizing<String>>, String> {
+        let path = record_path(key)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(&path).map_err(|e| format!("读取文件失败: {}", e))?;
+        let envelope: Envelope = serde_json::from_str(&text)
+            .map_err(|_| format!("{} 不是保险库格式的数据", key))?;
+
+        let plain = self.with_key(|k| {
+            let plain = open(k, key, &envelope)?;
+            String::from_utf8(plain.to_vec())
+                .map(Zeroizing::new)
+                .map_err(|_| "解密结果不是有效的 UTF-8 文本".to_string())
+        })?;
+        let (records, migrated) = decode_document(key, &plain)?;
+        let records = Zeroizing::new(serde_json::to_string(&records).map_err(|e| e.to_string())?);
+        if migrated {
+            self.store_record(key, &records)?;
+        }
+        Ok(Some(records))
+    }
+
+    // 校验记录结构后加密写入
+    pub fn store_record(&self, key: &str, data: &str) -> Result<(), String> {
+        let document = Zeroizing::new(encode_document(key, data)?);
+        let envelope = self.with_key(|k| seal(k, key, document.as_bytes()))?;
+        let text = serde_json::to_string(&envelope).map_err(|e| e.to_string())?;
+        write_record(key, text.as_bytes())
+    }
+}
+
+#[command]
+pub fn vault_status(state: State<'_, VaultState>) -> Result<VaultStatus, String> {
+    Ok(VaultStatus {
+        initialized: read_meta()?.is_some(),
+        unlocked: state.is_unlocked(),
+    })
+}
+
+#[command]
+pub fn vault_unlock(state: State<'_, VaultState>, password: String) -> Result<VaultStatus, String> {
+    let password = Zeroizing::new(password);
+    if password.is_empty() {
+        return Err("主密码不能为空".to_string());
+    }
+
+    let key = match read_meta()? {
+        Some(meta) => {
+            let key = derive_key(&password, &meta.kdf)?;
+            open(&key, VAULT_META_FILE, &meta.check).map_err(|_| "主密码错误".to_string())?;
+            key
+        }
+        None => init_meta(&password)?.1,
+    };
+
+    let mut guard = state.session.lock().map_err(|_| "保险库状态异常".to_string())?;
+    *guard = Some(VaultSession { key, last_used: Instant::now() });
+    Ok(VaultStatus { initialized: true, unlocked: true })
+}
+
+#[command]
+pub fn vault_lock(state: State<'_, VaultState>) -> Result<(), String> {
+    let mut guard = state.session.lock().map_err(|_| "保险库状态异常".to_string())?;
+    // 会话密钥为 Zeroizing，丢弃时会被清零
+    *guard = None;
+    Ok(())
+}
+
+// OSS 配置中的凭证不返回给前端
+#[command]
+pub fn vault_get(state: State<'_, VaultState>, key: String) -> Result<Option<String>, String> {
+    let Some(text) = state.load_record(&key)? else { return Ok(None) };
+    if key == CLOUD_OSS_CONFIGS_KEY {
+        return redact_oss_configs(&text).map(Some);
+    }
+    Ok(Some(text.to_string()))
+}
+
+#[command]
+pub fn vault_put(state: State<'_, VaultState>, key: String, data: String) -> Result<(), String> {
+    let data = Zeroizing::new(data);
+    if key == CLOUD_OSS_CONFIGS_KEY {
+        let stored = state.load_record(&key)?;
+        let merged = Zeroizing::new(restore_oss_secrets(&data, stored.as_deref().map(|s| s.as_str()))?);
+        return state.store_record(&key, &merged);
+    }
+    state.store_record(&key, &data)
+}
@@ -0,0 +1,85 @@
+// S3 兼容接口返回的 XML 结构简单，按标签截取即可，不引入完整的 XML 解析器
+
+// 找到 <tag> 或 <tag 属性…> 的起始位置，排除 <TagSet> 这类前缀相同的标签
+fn find_open(doc: &str, tag: &str, from: usize) -> Option<(usize, usize, bool)> {
+    let pattern = format!("<{}", tag);
+    let mut pos = from;
+    while let Some(i) = doc[pos..].find(&pattern) {
+        let start = pos + i;
+        let after = start + pattern.len();
+        match doc[after..].chars().next() {
+            Some('>') => return Some((start, after + 1, false)),
+            Some(c) if c.is_whitespace() || c == '/' => {
+                let end = after + doc[after..].find('>')?;
+                return Some((start, end + 1, doc[..end].ends_with('/')));
+            }
+            _ => pos = after,
+        }
+    }
+    None
+}
+
+// 依次返回每个 <tag>…</tag> 的内容（不含标签本身），自闭合标签返回空字符串
+pub(crate) fn blocks<'a>(doc: &'a str, tag: &str) -> Vec<&'a str> {
+    let close = format!("</{}>", tag);
+    let mut out = Vec::new();
+    let mut pos = 0;
+    while let Some((_, content_start, self_closing)) = find_open(doc, tag, pos) {
+        if self_closing {
+            out.push("");
+            pos = content_start;
+            continue;
+        }
+        let Some(len) = doc[content_start..].find(&close) else { break };
+        out.push(&doc[content_start..content_start + len]);
+        pos = content_start + len + close.len();
+    }
+    out
+}
+
+// 第一个 <tag> 的文本内容，已去除首尾空白并反转义
+pub(crate) fn text(doc: &str, tag: &str) -> Option<String> {
+    blocks(doc, tag).first().map(|s| unescape(s.trim()))
+}
+
+pub(crate) fn unescape(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// 拼装请求体时转义文本内容，对象 key 可能包含 & < > 等字符
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_blocks_and_text() {
+        let doc = r#"<Tagging><TagSet><Tag><Key>a&amp;b</Key><Value>1</Value></Tag><Tag><Key>c</Key><Value/></Tag></TagSet></Tagging>"#;
+        let tags = blocks(doc, "Tag");
+        assert_eq!(tags.len(), 2);
+        assert_eq!(text(tags[0], "Key").as_deref(), Some("a&b"));
+        assert_eq!(text(tags[1], "Value").as_deref(), Some(""));
+        assert_eq!(blocks(doc, "TagSet").len(), 1);
+
+        let grant = r#"<Grantee xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Group"><URI>u</URI></Grantee>"#;
+        assert_eq!(text(grant, "URI").as_deref(), Some("u"));
+        assert_eq!(blocks(grant, "Grantee").len(), 1);
+
+        assert_eq!(unescape(&escape("a<&>'\"b")), "a<&>'\"b");
+    }
+}
@@ -44,6 +44,11 @@ const routes: RouteRecordRaw[] = [
     path: '/tools',
     name: 'tools',
     component: () => import('../views/Tools.vue')
+  },
+  {
+    path: '/settings',
+    name: 'settings',
+    component: () => import('../views/Settings.vue')
   }
 ]
 
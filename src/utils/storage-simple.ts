@@ -1,77 +1,64 @@
 import { invoke } from '@tauri-apps/api/tauri'
 
-// 简单的加密/解密函数 - 支持中文
-function simpleEncrypt(text: string, key: string): string {
-  try {
-    // 使用encodeURIComponent处理中文字符
-    const encodedText = encodeURIComponent(text)
-    let result = ''
-    for (let i = 0; i < encodedText.length; i++) {
-      result += String.fromCharCode(encodedText.charCodeAt(i) ^ key.charCodeAt(i % key.length))
-    }
-    return btoa(result)
-  } catch (error) {
-    // 如果加密失败，直接返回base64编码
-    return btoa(encodeURIComponent(text))
-  }
+export interface VaultStatus {
+  initialized: boolean
+  unlocked: boolean
 }
 
-function simpleDecrypt(encryptedText: string, key: string): string {
-  try {
-    const text = atob(encryptedText)
-    let result = ''
-    for (let i = 0; i < text.length; i++) {
-      result += String.fromCharCode(text.charCodeAt(i) ^ key.charCodeAt(i % key.length))
-    }
-    return decodeURIComponent(result)
-  } catch (error) {
-    // 如果解密失败，尝试直接解码
-    try {
-      return decodeURIComponent(atob(encryptedText))
-    } catch {
-      return encryptedText
-    }
-  }
+export interface DataBackup {
+  id: string
+  created_at: string
+  size: number
 }
 
-const ENCRYPTION_KEY = 'sensitive-info-tool-key-2024'
+export type ImportMode = 'merge' | 'replace'
+
+export interface ExportReport {
+  // 导出包写入的本地路径
+  path: string
+  exported: string[]
+  // 无法解密的数据键不进入导出包
+  failed: { key: string; error: string }[]
+}
+
+export interface ImportReport {
+  mode: ImportMode
+  created_at: string
+  keys: { key: string; action: 'created' | 'replaced' | 'merged' | 'unchanged'; added: number; updated: number }[]
+  conflicts: { key: string; id?: string; resolution: 'kept_local' | 'took_incoming' }[]
+  failed: { key: string; error: string }[]
+}
+
+export interface MigrationReport {
+  migrated: { key: string; source: 'xor' | 'base64' | 'plain'; origin: 'file' | 'local_storage'; records: number }[]
+  skipped: string[]
+  failed: { key: string; error: string }[]
+  removed_files: number
+}
+
+// 旧版本写入 localStorage 缓存的数据键
+const LEGACY_DATA_KEYS = ['cloud_oss_configs', 'sensitive_wechat', 'sensitive_enterprise', 'sensitive_feishu', 'sensitive_dingtalk']
 
 export class SimpleStorage {
-  // 获取数据文件路径
-  private static async getDataFilePath(key: string): Promise<string> {
-    try {
-      const homeDir = await invoke<string>('get_home_dir')
-      return `${homeDir}/.sensitive-info-tool/${key}.json`
-    } catch (error) {
-      console.error('获取数据文件路径失败:', error)
-      throw error
-    }
+  // 查询保险库状态
+  static async vaultStatus(): Promise<VaultStatus> {
+    return await invoke<VaultStatus>('vault_status')
   }
 
-  // 确保数据目录存在
-  private static async ensureDataDir(): Promise<void> {
-    try {
-      await invoke('ensure_data_dir')
-    } catch (error) {
-      console.error('创建数据目录失败:', error)
-      throw error
-    }
+  // 使用主密码解锁保险库（首次调用时会以该密码初始化保险库）
+  static async unlock(password: string): Promise<VaultStatus> {
+    return await invoke<VaultStatus>('vault_unlock', { password })
   }
 
-  // 保存数据到文件
+  // 锁定保险库
+  static async lock(): Promise<void> {
+    await invoke('vault_lock')
+  }
+
+  // 保存数据（由后端加密后落盘）
   static async saveData(key: string, data: any): Promise<void> {
     try {
-      await this.ensureDataDir()
-      const filePath = await this.getDataFilePath(key)
-      const encryptedData = simpleEncrypt(JSON.stringify(data), ENCRYPTION_KEY)
-      
-      await invoke('write_data_file', { 
-        filePath, 
-        data: encryptedData 
-      })
-      
-      // 同时保存到localStorage作为缓存
-      localStorage.setItem(key, encryptedData)
+      await invoke('vault_put', { key, data: JSON.stringify(data) })
       console.log('数据保存成功:', key)
     } catch (error) {
       console.error('保存数据失败:', error)
@@ -79,45 +66,38 @@ export class SimpleStorage {
     }
   }
 
-  // 从文件读取数据
+  // 读取数据（由后端解密）
   static async loadData(key: string): Promise<any> {
     try {
-      // 首先尝试从localStorage读取缓存
-      const cachedData = localStorage.getItem(key)
-      if (cachedData) {
-        try {
-          const decryptedData = simpleDecrypt(cachedData, ENCRYPTION_KEY)
-          return JSON.parse(decryptedData)
-        } catch {
-          // 缓存数据损坏，继续从文件读取
-        }
-      }
-
-      // 从文件读取数据
-      const filePath = await this.getDataFilePath(key)
-      const encryptedData = await invoke<string>('read_data_file', { filePath })
-      
-      if (!encryptedData) return null
-      
-      const decryptedData = simpleDecrypt(encryptedData, ENCRYPTION_KEY)
-      const parsedData = JSON.parse(decryptedData)
-      
-      // 更新缓存
-      localStorage.setItem(key, encryptedData)
-      
-      return parsedData
+      const plain = await invoke<string | null>('vault_get', { key })
+      if (plain) return JSON.parse(plain)
+      return null
     } catch (error) {
       console.error('读取数据失败:', error)
       return null
     }
   }
 
+  // 将旧版 XOR 格式的数据迁移到保险库，并清理旧版本留在 localStorage 中的缓存；
+  // 数据文件缺失、只剩 localStorage 缓存的数据一并交给后端迁移，失败的缓存保留以便下次重试
+  static async migrateLegacy(): Promise<MigrationReport> {
+    const localCache: Record<string, string> = {}
+    for (const key of LEGACY_DATA_KEYS) {
+      const cached = localStorage.getItem(key)
+      if (cached) localCache[key] = cached
+    }
+    const report = await invoke<MigrationReport>('migrate_legacy_data', { localCache })
+    const keys = [...report.migrated.map(m => m.key), ...report.skipped]
+    for (const key of keys) {
+      localStorage.removeItem(key)
+    }
+    return report
+  }
+
   // 删除数据
   static async deleteData(key: string): Promise<void> {
     try {
-      const filePath = await this.getDataFilePath(key)
-      await invoke('delete_data_file', { filePath })
-      localStorage.removeItem(key)
+      await invoke('delete_data_file', { key })
       console.log('数据删除成功:', key)
     } catch (error) {
       console.error('删除数据失败:', error)
@@ -135,4 +115,25 @@ export class SimpleStorage {
       return []
     }
   }
+
+  // 列出某个数据键的历史版本
+  static async listBackups(key: string): Promise<DataBackup[]> {
+    return await invoke<DataBackup[]>('list_data_backups', { key })
+  }
+
+  // 恢复到指定的历史版本
+  static async restoreBackup(key: string, backupId: string): Promise<void> {
+    await invoke('restore_data_backup', { key, backupId })
+  }
+
+  // 导出所有数据为口令加密的导出包；需再次输入主密码，由后端弹出保存对话框写入文件，
+  // 导出包内容不返回前端，用户取消保存时返回 null
+  static async exportBundle(password: string, passphrase: string): Promise<ExportReport | null> {
+    return await invoke<ExportReport | null>('export_bundle', { password, passphrase })
+  }
+
+  // 导入导出包
+  static async importBundle(bundle: string, passphrase: string, mode: ImportMode): Promise<ImportReport> {
+    return await invoke<ImportReport>('import_bundle', { bundle, passphrase, mode })
+  }
 }
@@ -13,12 +13,18 @@ export interface OssConfig {
   region?: string
   endpoint?: string
   bucket?: string
+  session_token?: string
+  expiration?: string
 }
 
 export interface OssListParams {
   prefix?: string
   delimiter?: string
   max_keys?: number
+  // 上一页返回的 next_token
+  continuation_token?: string
+  // 上一页返回的 next_start_after
+  start_after?: string
 }
 
 export interface OssFileItem {
@@ -26,6 +32,25 @@ export interface OssFileItem {
   size: number
   last_modified?: string
   is_directory: boolean
+  etag?: string
+  storage_class?: string
+  owner_id?: string
+  owner_display_name?: string
+  checksum_algorithm?: string
+}
+
+export interface OssListPage {
+  items: OssFileItem[]
+  next_token?: string
+  next_start_after?: string
+  is_truncated: boolean
+}
+
+// PermanentRedirect 修正出的实际 region/endpoint
+export interface EndpointCorrection {
+  region: string
+  endpoint: string
+  saved: boolean
 }
 
 export interface OssResult<T = any> {
@@ -33,11 +58,22 @@ export interface OssResult<T = any> {
   data?: T
   message?: string
   error?: string
+  correction?: EndpointCorrection
 }
 
-export async function ossListObjects(config: OssConfig, params?: OssListParams): Promise<OssResult<OssFileItem[]>> {
+export async function ossListObjects(config: OssConfig, params?: OssListParams): Promise<OssResult<OssListPage>> {
   try {
-    const data = await invoke<OssResult<OssFileItem[]>>('oss_list_objects', { cfg: config, params })
+    const data = await invoke<OssResult<OssListPage>>('oss_list_objects', { cfg: config, params })
+    return data
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// 按已保存配置的 id 调用，凭证由后端从保险库读取
+export async function ossListObjectsById(configId: string, params?: OssListParams): Promise<OssResult<OssListPage>> {
+  try {
+    const data = await invoke<OssResult<OssListPage>>('oss_list_objects_by_id', { configId, params })
     return data
   } catch (e: any) {
     return { success: false, error: e?.message || String(e) }
@@ -113,3 +149,499 @@ export async function ossListBuckets(config: OssConfig): Promise<OssResult<OssBu
     return { success: false, error: e?.message || String(e) }
   }
 }
+
+export async function ossUploadFileById(configId: string, params: OssUploadParams): Promise<OssResult<string>> {
+  try {
+    const data = await invoke<OssResult<string>>('oss_upload_file_by_id', { configId, params })
+    return data
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossDownloadFileById(configId: string, params: OssDownloadParams): Promise<OssResult<number[]>> {
+  try {
+    const data = await invoke<OssResult<number[]>>('oss_download_file_by_id', { configId, params })
+    return data
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossDeleteFileById(configId: string, params: OssDeleteParams): Promise<OssResult<string>> {
+  try {
+    const data = await invoke<OssResult<string>>('oss_delete_file_by_id', { configId, params })
+    return data
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossCreateFolderById(configId: string, params: OssCreateFolderParams): Promise<OssResult<string>> {
+  try {
+    const data = await invoke<OssResult<string>>('oss_create_folder_by_id', { configId, params })
+    return data
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossListBucketsById(configId: string): Promise<OssResult<OssBucketSummary[]>> {
+  try {
+    const data = await invoke<OssResult<OssBucketSummary[]>>('oss_list_buckets_by_id', { configId })
+    return data
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 存储桶创建与删除 =====
+export interface OssCreateBucketParams {
+  bucket: string
+  region?: string
+  acl?: 'private' | 'public-read' | 'public-read-write' | 'authenticated-read'
+  // 仅阿里云与华为云支持
+  storage_class?: string
+}
+
+export interface OssDeleteBucketParams {
+  bucket: string
+  // 需与 bucket 一致
+  confirm_name: string
+  empty_first?: boolean
+}
+
+export interface OssDeleteBucketSummary {
+  bucket: string
+  deleted_objects: number
+  failed: { key: string; code?: string; error: string }[]
+}
+
+export async function ossCreateBucketById(configId: string, params: OssCreateBucketParams): Promise<OssResult<string>> {
+  try {
+    return await invoke<OssResult<string>>('oss_create_bucket_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossDeleteBucketById(configId: string, params: OssDeleteBucketParams): Promise<OssResult<OssDeleteBucketSummary>> {
+  try {
+    return await invoke<OssResult<OssDeleteBucketSummary>>('oss_delete_bucket_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 存储桶安全检查 =====
+export interface OssBucketSecurityParams {
+  // 为空时抽样桶内前 sample_size 个对象（默认 5，最多 20）
+  sample_keys?: string[]
+  sample_size?: number
+}
+
+export interface OssAcl {
+  owner_id?: string
+  owner_display_name?: string
+  grants: { grantee_type: string; grantee: string; display_name?: string; permission: string }[]
+}
+
+export interface OssBucketSecurity {
+  bucket: string
+  acl?: OssAcl
+  policy?: any
+  policy_error?: string
+  public_access_block?: {
+    block_public_acls: boolean
+    ignore_public_acls: boolean
+    block_public_policy: boolean
+    restrict_public_buckets: boolean
+  }
+  public_access_block_error?: string
+  object_acls: { key: string; acl?: OssAcl; error?: string }[]
+  // 凭证列举失败、无法抽样对象时的原因，此时不检查对象 ACL
+  sample_error?: string
+  risks: string[]
+  // public-write / public-read / authenticated-users / private；有配置读取失败且未发现风险时为 unknown
+  verdict: string
+  findings: string[]
+}
+
+export async function ossGetBucketSecurityById(configId: string, params: OssBucketSecurityParams = {}): Promise<OssResult<OssBucketSecurity>> {
+  try {
+    return await invoke<OssResult<OssBucketSecurity>>('oss_get_bucket_security_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 匿名访问检查 =====
+export interface OssAnonymousAccessParams {
+  bucket?: string
+  // 为空时取桶内第一个对象
+  key?: string
+}
+
+export interface OssAnonymousAccess {
+  bucket: string
+  key?: string
+  checks: {
+    operation: 'list' | 'get' | 'head'
+    url: string
+    status?: number
+    allowed: boolean
+    code?: string
+    error?: string
+  }[]
+  allowed_operations: string[]
+  // 凭证列举失败、无法选取样本对象时的原因，此时跳过 get/head 检查
+  sample_error?: string
+}
+
+export async function ossCheckAnonymousAccessById(configId: string, params: OssAnonymousAccessParams = {}): Promise<OssResult<OssAnonymousAccess>> {
+  try {
+    return await invoke<OssResult<OssAnonymousAccess>>('oss_check_anonymous_access_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 存储桶配置 =====
+export interface OssCorsRule {
+  id?: string
+  allowed_origins: string[]
+  allowed_methods: string[]
+  allowed_headers: string[]
+  expose_headers: string[]
+  max_age_seconds?: number
+}
+
+export interface OssLifecycleRule {
+  id?: string
+  status?: string
+  prefix?: string
+  expiration_days?: number
+  expiration_date?: string
+  noncurrent_expiration_days?: number
+  abort_incomplete_upload_days?: number
+  transitions: { days?: number; date?: string; storage_class?: string }[]
+}
+
+// 未设置的配置项为空；读取失败的项在 errors 中，键为配置项名称（cors、lifecycle 等）
+export interface OssBucketDescription {
+  bucket: string
+  cors?: OssCorsRule[]
+  lifecycle?: OssLifecycleRule[]
+  versioning?: { status?: string; mfa_delete?: string }
+  encryption?: { algorithm?: string; kms_key_id?: string; bucket_key_enabled?: boolean }
+  logging?: { target_bucket: string; target_prefix?: string }
+  website?: { index_document?: string; error_document?: string; redirect_all_to?: string; routing_rule_count: number }
+  replication?: {
+    role?: string
+    rules: { id?: string; status?: string; prefix?: string; destination_bucket?: string; storage_class?: string }[]
+  }
+  errors: Record<string, string>
+}
+
+export async function ossDescribeBucketById(configId: string): Promise<OssResult<OssBucketDescription>> {
+  try {
+    return await invoke<OssResult<OssBucketDescription>>('oss_describe_bucket_by_id', { configId })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 预签名链接 =====
+export interface OssPresignParams {
+  key: string
+  method?: 'GET' | 'PUT'
+  // 有效期（秒），默认 3600，最长 7 天
+  expires_secs?: number
+  // 仅 GET 可用：下载时覆盖的响应头
+  response_content_type?: string
+  response_content_disposition?: string
+  response_cache_control?: string
+}
+
+export interface OssPresignedUrl {
+  url: string
+  method: string
+  // 使用 STS 临时凭证时不晚于凭证过期时间
+  expires_at: string
+}
+
+export async function ossPresignById(configId: string, params: OssPresignParams): Promise<OssResult<OssPresignedUrl>> {
+  try {
+    return await invoke<OssResult<OssPresignedUrl>>('oss_presign_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 对象元数据 =====
+export interface OssObjectMetadata {
+  key: string
+  size: number
+  content_type?: string
+  etag?: string
+  last_modified?: string
+  // 未返回存储类型时为 STANDARD
+  storage_class: string
+  server_side_encryption?: string
+  kms_key_id?: string
+  version_id?: string
+  cache_control?: string
+  content_disposition?: string
+  content_encoding?: string
+  expires?: string
+  // 自定义元数据，键不含 x-amz-meta- / x-oss-meta- 等前缀
+  user_metadata: Record<string, string>
+  tag_count?: number
+  // 无权读取标签时为空
+  tags?: Record<string, string>
+}
+
+export async function ossHeadObjectById(configId: string, key: string): Promise<OssResult<OssObjectMetadata>> {
+  try {
+    return await invoke<OssResult<OssObjectMetadata>>('oss_head_object_by_id', { configId, params: { key } })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 服务端复制与移动 =====
+export interface OssCopyParams {
+  // 以 / 结尾时处理整个前缀
+  source_key: string
+  dest_key: string
+  dry_run?: boolean
+  overwrite?: boolean
+}
+
+export interface OssCopySummary {
+  dry_run: boolean
+  items: { source_key: string; dest_key: string; size: number }[]
+  conflicts: string[]
+  copied: number
+  failed: { key: string; error: string }[]
+}
+
+// oss-copy-progress 事件内容
+export interface CopyProgress {
+  source_key: string
+  dest_key: string
+  done: number
+  total: number
+  bytes_done: number
+  bytes_total: number
+}
+
+export const COPY_PROGRESS_EVENT = 'oss-copy-progress'
+
+export async function ossCopyObjectById(configId: string, params: OssCopyParams): Promise<OssResult<OssCopySummary>> {
+  try {
+    return await invoke<OssResult<OssCopySummary>>('oss_copy_object_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossMoveObjectById(configId: string, params: OssCopyParams): Promise<OssResult<OssCopySummary>> {
+  try {
+    return await invoke<OssResult<OssCopySummary>>('oss_move_object_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 按前缀批量删除 =====
+export interface OssDeletePrefixParams {
+  prefix: string
+  dry_run?: boolean
+  // 预览时返回，执行删除时必须带上
+  confirm_token?: string
+}
+
+export interface OssDeletePrefixSummary {
+  dry_run: boolean
+  prefix: string
+  count: number
+  total_size: number
+  confirm_token?: string
+  deleted: number
+  failed: { key: string; code?: string; error: string }[]
+}
+
+export const DELETE_PROGRESS_EVENT = 'oss-delete-progress'
+
+export async function ossDeletePrefixById(configId: string, params: OssDeletePrefixParams): Promise<OssResult<OssDeletePrefixSummary>> {
+  try {
+    return await invoke<OssResult<OssDeletePrefixSummary>>('oss_delete_prefix_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+// ===== 大文件分片上传 =====
+export interface OssUploadPathParams {
+  local_path: string
+  path?: string
+  file_name?: string
+  content_type?: string
+  part_size_mb?: number
+  concurrency?: number
+  // 续传时传入中断前的 upload_id
+  upload_id?: string
+}
+
+// oss-upload-progress 事件内容
+export interface UploadProgress {
+  upload_id: string
+  key: string
+  uploaded_bytes: number
+  total_bytes: number
+  parts_done: number
+  parts_total: number
+}
+
+export interface UploadCheckpoint {
+  upload_id: string
+  bucket: string
+  key: string
+  local_path: string
+  file_size: number
+  part_size: number
+  parts: { part_number: number; etag: string; size: number }[]
+  created_at: string
+}
+
+export const UPLOAD_PROGRESS_EVENT = 'oss-upload-progress'
+
+export async function ossUploadPathById(configId: string, params: OssUploadPathParams): Promise<OssResult<string>> {
+  try {
+    return await invoke<OssResult<string>>('oss_upload_path_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossAbortUploadById(configId: string, uploadId: string): Promise<OssResult<string>> {
+  try {
+    return await invoke<OssResult<string>>('oss_abort_upload_by_id', { configId, uploadId })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossListResumableUploads(): Promise<UploadCheckpoint[]> {
+  return await invoke<UploadCheckpoint[]>('oss_list_resumable_uploads')
+}
+
+// ===== 流式下载到本地文件 =====
+export interface OssDownloadPathParams {
+  key: string
+  local_path: string
+  overwrite?: boolean
+}
+
+// oss-download-progress 事件内容
+export interface DownloadProgress {
+  key: string
+  local_path: string
+  downloaded_bytes: number
+  total_bytes: number
+}
+
+export interface DownloadSummary {
+  local_path: string
+  bytes: number
+  verified_by?: 'crc64' | 'md5'
+}
+
+export const DOWNLOAD_PROGRESS_EVENT = 'oss-download-progress'
+
+export async function ossDownloadToPathById(configId: string, params: OssDownloadPathParams): Promise<OssResult<DownloadSummary>> {
+  try {
+    return await invoke<OssResult<DownloadSummary>>('oss_download_to_path_by_id', { configId, params })
+  } catch (e: any) {
+    return { success: false, error: e?.message || String(e) }
+  }
+}
+
+export async function ossCancelDownload(localPath: string): Promise<void> {
+  await invoke('oss_cancel_download', { localPath })
+}
+
+// ===== 传输队列 =====
+export type TransferStatus = 'queued' | 'running' | 'paused' | 'completed' | 'failed' | 'cancelled'
+
+export type TransferRequest =
+  | ({ kind: 'upload' } & OssUploadPathParams)
+  | ({ kind: 'download' } & OssDownloadPathParams)
+
+// transfer-updated 事件内容
+export interface TransferJob {
+  id: string
+  config_id: string
+  request: TransferRequest
+  status: TransferStatus
+  transferred_bytes: number
+  total_bytes: number
+  error?: string
+  created_at: string
+  updated_at: string
+}
+
+export const TRANSFER_EVENT = 'transfer-updated'
+
+export async function transferEnqueueUpload(configId: string, params: OssUploadPathParams): Promise<TransferJob> {
+  return await invoke<TransferJob>('transfer_enqueue_upload', { configId, params })
+}
+
+export async function transferEnqueueDownload(configId: string, params: OssDownloadPathParams): Promise<TransferJob> {
+  return await invoke<TransferJob>('transfer_enqueue_download', { configId, params })
+}
+
+export async function transferList(): Promise<TransferJob[]> {
+  return await invoke<TransferJob[]>('transfer_list')
+}
+
+export async function transferPause(id: string): Promise<TransferJob> {
+  return await invoke<TransferJob>('transfer_pause', { id })
+}
+
+export async function transferResume(id: string): Promise<TransferJob> {
+  return await invoke<TransferJob>('transfer_resume', { id })
+}
+
+export async function transferCancel(id: string): Promise<TransferJob> {
+  return await invoke<TransferJob>('transfer_cancel', { id })
+}
+
+export async function transferClearFinished(): Promise<TransferJob[]> {
+  return await invoke<TransferJob[]>('transfer_clear_finished')
+}
+
+// 用户确认后把后端记录的修正结果写回已保存的配置，前端只传配置 id
+export async function ossApplyEndpointCorrection(configId: string): Promise<EndpointCorrection> {
+  return await invoke<EndpointCorrection>('oss_apply_endpoint_correction', { configId })
+}
+
+// ===== 应用设置 =====
+export type LogLevel = 'off' | 'error' | 'warn' | 'info' | 'debug' | 'trace'
+
+export interface AppSettings {
+  auto_lock_minutes: number
+  log_level: LogLevel
+  auto_apply_endpoint_correction: boolean
+  // 传输队列同时进行的任务数（1 ~ 8）
+  max_concurrent_transfers: number
+}
+
+export async function getSettings(): Promise<AppSettings> {
+  return await invoke<AppSettings>('get_settings')
+}
+
+export async function updateSettings(settings: AppSettings): Promise<AppSettings> {
+  return await invoke<AppSettings>('update_settings', { settings })
+}